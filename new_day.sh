@@ -20,9 +20,9 @@ fi
 
 # Create the day file
 cat > "$DAY_FILE" << 'EOF'
-use crate::read_input;
+use crate::{Options, read_input};
 
-pub fn solve() {
+pub fn solve(_options: &Options) {
     let input = read_input(DAY_NUM);
 
     println!("Part 1: {}", part1(&input));
@@ -72,9 +72,9 @@ else
 fi
 
 # Update src/main.rs
-if ! grep -q "$DAY => days::day${DAY_PADDED}::solve()" src/main.rs; then
+if ! grep -q "$DAY => days::day${DAY_PADDED}::solve(&options)" src/main.rs; then
     # Insert before the _ => default case
-    sed -i.bak "s/        _ => /        $DAY => days::day${DAY_PADDED}::solve(),\\
+    sed -i.bak "s/        _ => /        $DAY => days::day${DAY_PADDED}::solve(\&options),\\
         _ => /" src/main.rs && rm src/main.rs.bak
     echo "✓ Added day to src/main.rs"
 else
@@ -0,0 +1,210 @@
+use num_bigint::BigInt;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A puzzle answer that never silently wraps.
+///
+/// Values are kept as an `i128` while they fit and switch to a `BigInt` when
+/// an operation would overflow. A value that fits in `i128` is always stored
+/// as `Int`, so derived equality works across both representations.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::answer::Answer;
+/// let big: Answer = [u64::MAX; 4].into_iter().map(Answer::from).product();
+/// assert_eq!(big.to_string(), "115792089237316195398462578067141184799968521174335529155754622898352762650625");
+/// assert_eq!(big * Answer::from(0), Answer::from(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    Big(BigInt),
+}
+
+impl Answer {
+    fn from_big(big: BigInt) -> Self {
+        match i128::try_from(&big) {
+            Ok(v) => Answer::Int(v),
+            Err(_) => Answer::Big(big),
+        }
+    }
+
+    fn to_big(&self) -> BigInt {
+        match self {
+            Answer::Int(v) => BigInt::from(*v),
+            Answer::Big(b) => b.clone(),
+        }
+    }
+
+    /// Truncating division, or `None` when dividing by zero.
+    pub fn checked_div(self, rhs: Answer) -> Option<Answer> {
+        match (&self, &rhs) {
+            (_, Answer::Int(0)) => None,
+            (Answer::Int(a), Answer::Int(b)) if a.checked_div(*b).is_some() => {
+                Some(Answer::Int(a / b))
+            }
+            _ => Some(Answer::from_big(self.to_big() / rhs.to_big())),
+        }
+    }
+
+    /// The answer as an `i128`, if it fits.
+    pub fn to_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Int(v) => Some(*v),
+            Answer::Big(_) => None,
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Self {
+                    Answer::Int(v as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(v: u128) -> Self {
+        match i128::try_from(v) {
+            Ok(v) => Answer::Int(v),
+            Err(_) => Answer::Big(BigInt::from(v)),
+        }
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(v: BigInt) -> Self {
+        Answer::from_big(v)
+    }
+}
+
+impl Default for Answer {
+    fn default() -> Self {
+        Answer::Int(0)
+    }
+}
+
+impl FromStr for Answer {
+    type Err = num_bigint::ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<i128>() {
+            Ok(v) => Ok(Answer::Int(v)),
+            Err(_) => s.parse::<BigInt>().map(Answer::from_big),
+        }
+    }
+}
+
+impl Ord for Answer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Answer::Int(a), Answer::Int(b)) => a.cmp(b),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl PartialOrd for Answer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{}", v),
+            Answer::Big(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+macro_rules! impl_checked_op {
+    ($trait:ident, $method:ident, $checked:ident, $assign_trait:ident, $assign:ident) => {
+        impl $trait for Answer {
+            type Output = Answer;
+
+            fn $method(self, rhs: Answer) -> Answer {
+                if let (Answer::Int(a), Answer::Int(b)) = (&self, &rhs)
+                    && let Some(v) = a.$checked(*b)
+                {
+                    return Answer::Int(v);
+                }
+                Answer::from_big(self.to_big().$method(rhs.to_big()))
+            }
+        }
+
+        impl $assign_trait for Answer {
+            fn $assign(&mut self, rhs: Answer) {
+                *self = std::mem::take(self).$method(rhs);
+            }
+        }
+    };
+}
+
+impl_checked_op!(Add, add, checked_add, AddAssign, add_assign);
+impl_checked_op!(Sub, sub, checked_sub, SubAssign, sub_assign);
+impl_checked_op!(Mul, mul, checked_mul, MulAssign, mul_assign);
+
+impl Sum for Answer {
+    fn sum<I: Iterator<Item = Answer>>(iter: I) -> Self {
+        iter.fold(Answer::Int(0), Add::add)
+    }
+}
+
+impl Product for Answer {
+    fn product<I: Iterator<Item = Answer>>(iter: I) -> Self {
+        iter.fold(Answer::Int(1), Mul::mul)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promotes_on_overflow() {
+        let max = Answer::from(i128::MAX);
+        let over = max.clone() + Answer::from(1);
+        assert!(matches!(over, Answer::Big(_)));
+        assert_eq!(over.to_string(), "170141183460469231731687303715884105728");
+
+        // Coming back into range demotes to the small representation
+        assert_eq!(over - Answer::from(1), max);
+    }
+
+    #[test]
+    fn test_division_and_ordering() {
+        let min = Answer::from(i128::MIN);
+        assert_eq!(Answer::from(7).checked_div(Answer::from(2)), Some(3.into()));
+        assert_eq!(Answer::from(7).checked_div(Answer::from(0)), None);
+        assert_eq!(
+            min.clone()
+                .checked_div(Answer::from(-1))
+                .unwrap()
+                .to_string(),
+            "170141183460469231731687303715884105728"
+        );
+
+        let big = Answer::from(u128::MAX);
+        assert!(min < Answer::from(0) && Answer::from(0) < big);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("357".parse::<Answer>().unwrap(), Answer::from(357));
+        let digits = "9".repeat(50);
+        assert_eq!(digits.parse::<Answer>().unwrap().to_string(), digits);
+        assert!("12a".parse::<Answer>().is_err());
+    }
+}
@@ -1,71 +1,215 @@
-use crate::read_input;
+use crate::{Options, read_input};
+use std::str::FromStr;
 
-pub fn solve() {
+pub fn solve(_options: &Options) {
     let input = read_input(1);
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    match (part1(&input), part2(&input)) {
+        (Ok(p1), Ok(p2)) => {
+            println!("Part 1: {}", p1);
+            println!("Part 2: {}", p2);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("Invalid input: {}", e),
+    }
 }
 
-fn part1(input: &str) -> i32 {
-    let rotations = input.lines();
-    let mut dial: i32 = 50;
-    let mut password: i32 = 0;
+fn part1(input: &str) -> Result<u64, ParseError> {
+    Ok(Dial::new(100, 50)
+        .events(parse(input)?)
+        .filter(|e| e.kind == EventKind::LandedOnZero)
+        .map(|e| e.times)
+        .sum())
+}
 
-    for r in rotations {
-        let value: i32 = r[1..].parse().unwrap();
-        if r.starts_with("L") {
-            dial -= value;
-        } else {
-            dial += value;
-        }
-        if dial % 100 == 0 {
-            password += 1;
+fn part2(input: &str) -> Result<u64, ParseError> {
+    Ok(Dial::new(100, 50)
+        .events(parse(input)?)
+        .filter(|e| e.kind != EventKind::FullRotation)
+        .map(|e| e.times)
+        .sum())
+}
+
+/// Parse one rotation per line, skipping blank lines.
+fn parse(input: &str) -> Result<Vec<Rotation>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            l.parse().map_err(|error| ParseError {
+                line: i + 1,
+                text: l.to_string(),
+                error,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RotationError {
+    MissingAmount,
+    UnknownDirection(char),
+    InvalidAmount(std::num::ParseIntError),
+}
+
+impl std::fmt::Display for RotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RotationError::MissingAmount => write!(f, "missing rotation amount"),
+            RotationError::UnknownDirection(c) => {
+                write!(f, "unknown direction {:?}, expected L or R", c)
+            }
+            RotationError::InvalidAmount(e) => write!(f, "invalid amount: {}", e),
         }
     }
-    password
 }
 
-fn part2(input: &str) -> i32 {
-    let rotations = input.lines();
-    let mut dial: i32 = 50;
-    let mut password: i32 = 0;
+/// A malformed rotation, with its 1-based line number and original text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub error: RotationError,
+}
 
-    for r in rotations {
-        let value: i32 = r[1..].parse().unwrap();
-        password += value / 100; // full rotations
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {} {:?}: {}", self.line, self.text, self.error)
+    }
+}
 
-        // count times we rotate past 0
-        if r.starts_with("L") {
-            let was_at_zero = dial == 0;
-            dial -= value % 100;
-            if dial < 0 {
-                if !was_at_zero {
-                    password += 1;
-                }
-                dial += 100
-            }
-        } else {
-            dial += value % 100;
-            if dial > 99 {
-                if dial != 100 {
-                    password += 1;
-                }
-                dial -= 100;
-            }
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    pub dir: Direction,
+    pub amount: u64,
+}
+
+impl FromStr for Rotation {
+    type Err = RotationError;
+
+    /// Parse e.g. `L68` or ` r 4294967296 `, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut chars = s.chars();
+        let dir = match chars.next() {
+            Some('L' | 'l') => Direction::Left,
+            Some('R' | 'r') => Direction::Right,
+            Some(c) => return Err(RotationError::UnknownDirection(c)),
+            None => return Err(RotationError::MissingAmount),
+        };
+        let amount = chars.as_str().trim();
+        if amount.is_empty() {
+            return Err(RotationError::MissingAmount);
         }
+        let amount = amount.parse().map_err(RotationError::InvalidAmount)?;
+
+        Ok(Rotation { dir, amount })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// A click in the middle of a rotation pointed the dial at zero
+    PassedZero,
+    /// The rotation finished with the dial pointing at zero
+    LandedOnZero,
+    /// The rotation went once all the way around the dial
+    FullRotation,
+}
+
+/// Something that happened `times` times during the rotation at index `rotation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    pub rotation: usize,
+    pub kind: EventKind,
+    pub times: u64,
+}
 
-        // count times we land on 0
-        if dial == 0 {
-            password += 1;
+/// A dial with positions `0..size` that clicks one position per unit of rotation.
+#[derive(Debug, Clone)]
+pub struct Dial {
+    size: u64,
+    position: u64,
+}
+
+impl Dial {
+    pub fn new(size: u64, start: u64) -> Self {
+        assert!(size > 0, "dial must have at least one position");
+        assert!(start < size, "start must be a position on the dial");
+        Dial {
+            size,
+            position: start,
         }
     }
-    password
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Turn the dial and report what happened, without stepping click by click.
+    pub fn rotate(&mut self, index: usize, rotation: Rotation) -> Vec<Event> {
+        let Rotation { dir, amount } = rotation;
+        let shift = amount % self.size;
+
+        // Clicks needed before the dial first points at zero
+        let to_zero = match (dir, self.position) {
+            (_, 0) => self.size,
+            (Direction::Left, p) => p,
+            (Direction::Right, p) => self.size - p,
+        };
+        let zero_clicks = if amount >= to_zero {
+            (amount - to_zero) / self.size + 1
+        } else {
+            0
+        };
+
+        self.position = match dir {
+            Direction::Left => (self.position + self.size - shift) % self.size,
+            Direction::Right => (self.position + shift) % self.size,
+        };
+        let landed = self.position == 0;
+        // When the rotation ends at zero its last click is the landing, not a pass
+        let passes = zero_clicks - u64::from(landed && amount > 0);
+
+        [
+            (EventKind::PassedZero, passes),
+            (EventKind::LandedOnZero, u64::from(landed)),
+            (EventKind::FullRotation, amount / self.size),
+        ]
+        .into_iter()
+        .filter(|&(_, times)| times > 0)
+        .map(|(kind, times)| Event {
+            rotation: index,
+            kind,
+            times,
+        })
+        .collect()
+    }
+
+    /// Stream the events of applying `rotations` in order.
+    pub fn events<I>(mut self, rotations: I) -> impl Iterator<Item = Event>
+    where
+        I: IntoIterator<Item = Rotation>,
+    {
+        rotations
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(i, r)| self.rotate(i, r))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::xorshift;
 
     const EXAMPLE: &str = "\
 L68
@@ -81,11 +225,154 @@ L82";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 3);
+        assert_eq!(part1(EXAMPLE), Ok(3));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 6);
+        assert_eq!(part2(EXAMPLE), Ok(6));
+    }
+
+    /// Step the dial one click at a time, tallying (passes, landings, full rotations).
+    fn simulate(size: u64, start: u64, rotations: &[Rotation]) -> (u64, u64, u64) {
+        let (mut passes, mut landings, mut laps) = (0, 0, 0);
+        let mut position = start;
+
+        for r in rotations {
+            for click in 1..=r.amount {
+                position = match r.dir {
+                    Direction::Left => (position + size - 1) % size,
+                    Direction::Right => (position + 1) % size,
+                };
+                if click.is_multiple_of(size) {
+                    laps += 1;
+                }
+                if position == 0 && click < r.amount {
+                    passes += 1;
+                }
+            }
+            if position == 0 {
+                landings += 1;
+            }
+        }
+        (passes, landings, laps)
+    }
+
+    #[test]
+    fn test_matches_step_by_step_simulation() {
+        let mut state = 0x2025_0001;
+
+        for _ in 0..500 {
+            let size = 1 + xorshift(&mut state) % 20;
+            let start = xorshift(&mut state) % size;
+            let rotations: Vec<Rotation> = (0..xorshift(&mut state) % 30)
+                .map(|_| Rotation {
+                    dir: if xorshift(&mut state).is_multiple_of(2) {
+                        Direction::Left
+                    } else {
+                        Direction::Right
+                    },
+                    amount: xorshift(&mut state) % (3 * size + 1),
+                })
+                .collect();
+
+            let mut totals = (0, 0, 0);
+            for e in Dial::new(size, start).events(rotations.iter().copied()) {
+                match e.kind {
+                    EventKind::PassedZero => totals.0 += e.times,
+                    EventKind::LandedOnZero => totals.1 += e.times,
+                    EventKind::FullRotation => totals.2 += e.times,
+                }
+            }
+
+            assert_eq!(
+                totals,
+                simulate(size, start, &rotations),
+                "size {} start {} {:?}",
+                size,
+                start,
+                rotations
+            );
+        }
+    }
+
+    #[test]
+    fn test_event_stream() {
+        let rotations = [
+            Rotation {
+                dir: Direction::Right,
+                amount: 250,
+            },
+            Rotation {
+                dir: Direction::Left,
+                amount: 0,
+            },
+        ];
+        let events: Vec<Event> = Dial::new(100, 50).events(rotations).collect();
+        assert_eq!(
+            events,
+            vec![
+                Event {
+                    rotation: 0,
+                    kind: EventKind::PassedZero,
+                    times: 2
+                },
+                Event {
+                    rotation: 0,
+                    kind: EventKind::LandedOnZero,
+                    times: 1
+                },
+                Event {
+                    rotation: 0,
+                    kind: EventKind::FullRotation,
+                    times: 2
+                },
+                Event {
+                    rotation: 1,
+                    kind: EventKind::LandedOnZero,
+                    times: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_formatting() {
+        let rotations = parse(" l68\nR48  \n\nr4294967296\n\n").unwrap();
+        assert_eq!(
+            rotations,
+            vec![
+                Rotation {
+                    dir: Direction::Left,
+                    amount: 68
+                },
+                Rotation {
+                    dir: Direction::Right,
+                    amount: 48
+                },
+                Rotation {
+                    dir: Direction::Right,
+                    amount: 4_294_967_296
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse("L68\nX30\nR48").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.text, "X30");
+        assert_eq!(err.error, RotationError::UnknownDirection('X'));
+
+        let err = parse("L68\nL30\nR").unwrap_err();
+        assert_eq!((err.line, err.error), (3, RotationError::MissingAmount));
+
+        let err = parse("L-5").unwrap_err();
+        assert!(matches!(err.error, RotationError::InvalidAmount(_)));
+        assert_eq!(
+            err.to_string(),
+            "line 1 \"L-5\": invalid amount: invalid digit found in string"
+        );
     }
 }
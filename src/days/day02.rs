@@ -1,92 +1,366 @@
-use crate::read_input;
+use crate::answer::Answer;
+use crate::{Options, read_input};
 
-pub fn solve() {
+pub fn solve(options: &Options) {
     let input = read_input(2);
 
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
+
+    if let Some(pattern) = Pattern::from_options(options) {
+        let total: Answer = parse(&input)
+            .map(|(start, end)| Answer::from(pattern.sum(start, end)))
+            .sum();
+        println!("{}: {}", pattern, total);
+    }
 }
 
-fn part1(input: &str) -> u64 {
-    let ranges = input.split(",");
-    ranges
-        .flat_map(|r| {
-            let (start, end) = r.split_once("-").unwrap();
-            find_matches(
-                start.trim().parse().unwrap(),
-                end.trim().parse().unwrap(),
-                is_double_block,
-            )
-        })
+fn part1(input: &str) -> Answer {
+    let pattern = Pattern::new(10, Repeats::Exactly(2)).unwrap();
+    parse(input)
+        .map(|(start, end)| Answer::from(pattern.sum(start, end)))
         .sum()
 }
 
-fn find_matches<F>(start: u64, end: u64, pred: F) -> Vec<u64>
-where
-    F: Fn(u64) -> bool,
-{
-    (start..=end).filter(|&n| pred(n)).collect()
+fn part2(input: &str) -> Answer {
+    let pattern = Pattern::new(10, Repeats::AtLeast(2)).unwrap();
+    parse(input)
+        .map(|(start, end)| Answer::from(pattern.sum(start, end)))
+        .sum()
 }
 
-fn is_double_block(v: u64) -> bool {
-    let s = v.to_string();
-    let len = s.len();
+fn parse(input: &str) -> impl Iterator<Item = (u64, u64)> + '_ {
+    input.split(",").map(|r| {
+        let (start, end) = r.split_once("-").unwrap();
+        (start.trim().parse().unwrap(), end.trim().parse().unwrap())
+    })
+}
 
-    if !len.is_multiple_of(2) {
-        return false;
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeats {
+    /// The digits are one block written exactly `n` times, e.g. `123123` for 2
+    Exactly(u32),
+    /// The digits are one block written `n` or more times, e.g. `121212` for 2
+    AtLeast(u32),
+}
 
-    let (a, b) = s.split_at(len / 2);
-    a == b
+/// Numbers whose digits in `base` are a single block repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern {
+    base: u128,
+    repeats: Repeats,
 }
 
-fn is_repeated_block(v: u64) -> bool {
-    let s = v.to_string();
-    if s.len() == 1 {
-        return false;
+impl Pattern {
+    pub fn new(base: u32, repeats: Repeats) -> Result<Self, String> {
+        if base < 2 {
+            return Err(format!("base must be at least 2, got {}", base));
+        }
+        if let Repeats::Exactly(0) | Repeats::AtLeast(0) = repeats {
+            return Err("repeat count must be at least 1".to_string());
+        }
+        Ok(Pattern {
+            base: base as u128,
+            repeats,
+        })
     }
-    let half_len = s.len() / 2;
 
-    for i in 1..=half_len {
-        if s.trim_start_matches(&s[0..i]).is_empty() {
-            return true;
+    /// Build a pattern from `--base`, `--min-repeats` and `--exact-repeats`.
+    /// Returns `None` if none of them were given.
+    pub fn from_options(options: &Options) -> Option<Self> {
+        let base: Option<u32> = options.value("--base");
+        let min: Option<u32> = options.value("--min-repeats");
+        let exact: Option<u32> = options.value("--exact-repeats");
+
+        let repeats = match (min, exact) {
+            (Some(_), Some(_)) => panic!("--min-repeats and --exact-repeats are exclusive"),
+            (Some(n), None) => Repeats::AtLeast(n),
+            (None, Some(n)) => Repeats::Exactly(n),
+            (None, None) if base.is_some() => Repeats::AtLeast(2),
+            (None, None) => return None,
+        };
+
+        Some(Pattern::new(base.unwrap_or(10), repeats).unwrap_or_else(|e| panic!("{}", e)))
+    }
+
+    /// Sum of all matching numbers in `start..=end`.
+    ///
+    /// Fits in a `u128`: the matches for one shape sum to less than
+    /// `base^(len + block_len)`, and `start..=end` is within `u64`.
+    ///
+    /// A `len`-digit number made of a `block_len`-digit block is `block * m` with
+    /// `m = 1 0..01 0..01` (the block repeated as a multiplier), so the matches for
+    /// each shape form an arithmetic series that can be summed directly.
+    pub fn sum(&self, start: u64, end: u64) -> u128 {
+        let mut total = 0;
+
+        for (lo, hi, len) in self.split_by_len(start, end) {
+            match self.repeats {
+                Repeats::Exactly(n) => {
+                    if len.is_multiple_of(n) {
+                        total += self.block_sum(lo, hi, len, len / n);
+                    }
+                }
+                Repeats::AtLeast(_) => {
+                    // Numbers with a short period also match every longer block
+                    // length that period divides, e.g. 111111 is 1x6, 11x3 and
+                    // 111x2. Inclusion-exclusion over the divisors keeps only the
+                    // numbers whose smallest block is exactly `block_len`.
+                    let block_lens = self.block_lens(len);
+                    let mut primitive: Vec<u128> = Vec::with_capacity(block_lens.len());
+                    for (i, &block_len) in block_lens.iter().enumerate() {
+                        let shorter: u128 = block_lens[..i]
+                            .iter()
+                            .zip(&primitive)
+                            .filter(|&(&d, _)| block_len.is_multiple_of(d))
+                            .map(|(_, &sum)| sum)
+                            .sum();
+                        primitive.push(self.block_sum(lo, hi, len, block_len) - shorter);
+                    }
+                    total += primitive.iter().sum::<u128>();
+                }
+            }
         }
+
+        total
+    }
+
+    /// All matching numbers in `start..=end`, each yielded once.
+    pub fn numbers(&self, start: u64, end: u64) -> impl Iterator<Item = u64> {
+        let pattern = *self;
+        self.split_by_len(start, end)
+            .flat_map(move |(lo, hi, len)| {
+                pattern
+                    .block_lens(len)
+                    .into_iter()
+                    .flat_map(move |block_len| {
+                        let mult = pattern.multiplier(len, block_len);
+                        let (first, last) = pattern.block_bounds(lo, hi, block_len, mult);
+                        (first..=last)
+                            .filter(move |&block| match pattern.repeats {
+                                Repeats::Exactly(_) => true,
+                                // Only emit a number for its smallest block so it is not
+                                // generated again for the longer blocks its period divides
+                                Repeats::AtLeast(_) => pattern.is_primitive(block, block_len),
+                            })
+                            .map(move |block| (block * mult) as u64)
+                    })
+            })
     }
-    false
-}
 
-fn part2(input: &str) -> u64 {
-    let ranges = input.split(",");
-    ranges
-        .flat_map(|r| {
-            let (start, end) = r.split_once("-").unwrap();
-            find_matches(
-                start.trim().parse().unwrap(),
-                end.trim().parse().unwrap(),
-                is_repeated_block,
-            )
+    /// Split `start..=end` into sub-ranges whose numbers all have the same digit count.
+    fn split_by_len(&self, start: u64, end: u64) -> impl Iterator<Item = (u128, u128, u32)> {
+        let base = self.base;
+        let (start, end) = (start.max(1) as u128, end as u128);
+        (self.num_digits(start)..=self.num_digits(end)).filter_map(move |len| {
+            let lo = start.max(base.pow(len - 1));
+            let hi = end.min(base.pow(len) - 1);
+            (lo <= hi).then_some((lo, hi, len))
         })
-        .sum()
+    }
+
+    fn num_digits(&self, mut v: u128) -> u32 {
+        let mut len = 1;
+        while v >= self.base {
+            v /= self.base;
+            len += 1;
+        }
+        len
+    }
+
+    /// Block lengths that can tile a number of `len` digits often enough.
+    fn block_lens(&self, len: u32) -> Vec<u32> {
+        match self.repeats {
+            Repeats::Exactly(n) if len.is_multiple_of(n) => vec![len / n],
+            Repeats::Exactly(_) => vec![],
+            Repeats::AtLeast(n) => (1..=len / n).filter(|&d| len.is_multiple_of(d)).collect(),
+        }
+    }
+
+    /// `1 0..01 0..01`: multiplying a `block_len`-digit block by this repeats it to `len` digits.
+    fn multiplier(&self, len: u32, block_len: u32) -> u128 {
+        (0..len / block_len)
+            .map(|i| self.base.pow(i * block_len))
+            .sum()
+    }
+
+    /// Range of `block_len`-digit blocks whose repetition lies within `lo..=hi`.
+    fn block_bounds(&self, lo: u128, hi: u128, block_len: u32, mult: u128) -> (u128, u128) {
+        let first = lo.div_ceil(mult).max(self.base.pow(block_len - 1));
+        let last = (hi / mult).min(self.base.pow(block_len) - 1);
+        (first, last)
+    }
+
+    fn block_sum(&self, lo: u128, hi: u128, len: u32, block_len: u32) -> u128 {
+        let mult = self.multiplier(len, block_len);
+        let (first, last) = self.block_bounds(lo, hi, block_len, mult);
+        if first > last {
+            return 0;
+        }
+        (first + last) * (last - first + 1) / 2 * mult
+    }
+
+    /// Whether a block is not itself a shorter block repeated.
+    fn is_primitive(&self, block: u128, block_len: u32) -> bool {
+        (1..block_len)
+            .filter(|&d| block_len.is_multiple_of(d))
+            .all(|d| !block.is_multiple_of(self.multiplier(block_len, d)))
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.repeats {
+            Repeats::Exactly(n) => write!(f, "Base {}, exactly {} repeats", self.base, n),
+            Repeats::AtLeast(n) => write!(f, "Base {}, at least {} repeats", self.base, n),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::xorshift;
 
     const EXAMPLE: &str = "\
 11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
+    fn is_double_block(v: u64) -> bool {
+        let s = v.to_string();
+        let len = s.len();
+
+        if !len.is_multiple_of(2) {
+            return false;
+        }
+
+        let (a, b) = s.split_at(len / 2);
+        a == b
+    }
+
+    fn is_repeated_block(v: u64) -> bool {
+        let s = v.to_string();
+        if s.len() == 1 {
+            return false;
+        }
+        let half_len = s.len() / 2;
+
+        for i in 1..=half_len {
+            if s.trim_start_matches(&s[0..i]).is_empty() {
+                return true;
+            }
+        }
+        false
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 1227775554);
+        assert_eq!(part1(EXAMPLE), 1227775554.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 4174379265);
+        assert_eq!(part2(EXAMPLE), 4174379265u64.into());
     }
     #[test]
     fn test_is_repeated_block() {
         assert!(is_repeated_block(888));
     }
+
+    /// Brute-force check on the digits of `v` in any base.
+    fn matches_by_digits(v: u64, base: u64, repeats: Repeats) -> bool {
+        let mut digits = Vec::new();
+        let mut rest = v;
+        while rest > 0 {
+            digits.push(rest % base);
+            rest /= base;
+        }
+        let len = digits.len();
+        (1..=len).filter(|&b| len.is_multiple_of(b)).any(|b| {
+            let count = (len / b) as u32;
+            let ok = match repeats {
+                Repeats::Exactly(n) => count == n,
+                Repeats::AtLeast(n) => count >= n,
+            };
+            ok && digits.chunks(b).all(|c| c == &digits[..b])
+        })
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut state = 0x2025_0002u64;
+
+        for _ in 0..300 {
+            let start = xorshift(&mut state) % 10u64.pow(1 + (xorshift(&mut state) % 8) as u32);
+            let end = start + xorshift(&mut state) % 20_000;
+
+            for (repeats, pred) in [
+                (Repeats::Exactly(2), is_double_block as fn(u64) -> bool),
+                (Repeats::AtLeast(2), is_repeated_block),
+            ] {
+                let pattern = Pattern::new(10, repeats).unwrap();
+                let expected: Vec<u64> = (start..=end).filter(|&n| pred(n)).collect();
+                let mut generated: Vec<u64> = pattern.numbers(start, end).collect();
+                generated.sort_unstable();
+
+                assert_eq!(generated, expected, "{:?} in {}-{}", repeats, start, end);
+                assert_eq!(
+                    pattern.sum(start, end),
+                    expected.iter().map(|&n| n as u128).sum::<u128>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_other_bases_and_counts() {
+        let mut state = 0x2025_0029u64;
+
+        for base in [2, 3, 7, 10, 16] {
+            for repeats in [
+                Repeats::Exactly(1),
+                Repeats::Exactly(3),
+                Repeats::AtLeast(1),
+                Repeats::AtLeast(3),
+                Repeats::AtLeast(4),
+            ] {
+                let pattern = Pattern::new(base, repeats).unwrap();
+                for _ in 0..10 {
+                    let start = xorshift(&mut state) % 1_000_000;
+                    let end = start + xorshift(&mut state) % 5_000;
+                    let expected: Vec<u64> = (start..=end)
+                        .filter(|&n| matches_by_digits(n, base as u64, repeats))
+                        .collect();
+                    let mut generated: Vec<u64> = pattern.numbers(start, end).collect();
+                    generated.sort_unstable();
+
+                    assert_eq!(generated, expected, "{} in {}-{}", pattern, start, end);
+                    assert_eq!(
+                        pattern.sum(start, end),
+                        expected.iter().map(|&n| n as u128).sum::<u128>()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(Pattern::new(1, Repeats::AtLeast(2)).is_err());
+        assert!(Pattern::new(10, Repeats::Exactly(0)).is_err());
+    }
+
+    #[test]
+    fn test_wide_range() {
+        let twice = Pattern::new(10, Repeats::Exactly(2)).unwrap();
+        assert_eq!(
+            twice.sum(1, u64::MAX),
+            12_509_613_850_169_742_155_792_778_978
+        );
+
+        let at_least_twice = Pattern::new(10, Repeats::AtLeast(2)).unwrap();
+        assert_eq!(
+            at_least_twice.numbers(1, 1_000_000).count(),
+            (1..=1_000_000).filter(|&n| is_repeated_block(n)).count()
+        );
+    }
 }
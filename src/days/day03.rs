@@ -1,40 +1,32 @@
-use crate::read_input;
+use crate::answer::Answer;
+use crate::subsequence::{SubsequenceError, largest_subsequence};
+use crate::{Options, read_input};
 
-pub fn solve() {
+pub fn solve(_options: &Options) {
     let input = read_input(3);
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    match (part1(&input), part2(&input)) {
+        (Ok(p1), Ok(p2)) => {
+            println!("Part 1: {}", p1);
+            println!("Part 2: {}", p2);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("Invalid input: {}", e),
+    }
 }
 
-fn part1(input: &str) -> u64 {
-    input.lines().map(|b| max_joltage_of_size(b, 2)).sum()
+fn part1(input: &str) -> Result<Answer, SubsequenceError> {
+    total_joltage(input, 2)
 }
 
-fn part2(input: &str) -> u64 {
-    input.lines().map(|b| max_joltage_of_size(b, 12)).sum()
+fn part2(input: &str) -> Result<Answer, SubsequenceError> {
+    total_joltage(input, 12)
 }
 
-fn max_joltage_of_size(s: &str, size: usize) -> u64 {
-    fn first_max(s: &str) -> Option<(usize, char)> {
-        s.chars().enumerate().fold(None, |acc, (i, c)| match acc {
-            None => Some((i, c)),
-            Some((_, max_c)) if c > max_c => Some((i, c)),
-            _ => acc,
-        })
-    }
-
-    let mut result = 0u64;
-    let mut start = 0;
-
-    for picks_remaining in (0..size).rev() {
-        let last = s.len() - picks_remaining;
-        let (relative_index, c) = first_max(&s[start..last]).unwrap();
-        start += relative_index + 1;
-        result = result * 10 + c.to_digit(10).unwrap() as u64
-    }
-
-    result
+fn total_joltage(input: &str, size: usize) -> Result<Answer, SubsequenceError> {
+    input
+        .lines()
+        .map(|bank| Ok(largest_subsequence(bank, size)?.value()))
+        .sum()
 }
 
 #[cfg(test)]
@@ -49,11 +41,29 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 357);
+        assert_eq!(part1(EXAMPLE), Ok(357.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 3121910778619);
+        assert_eq!(part2(EXAMPLE), Ok(3121910778619u64.into()));
+    }
+
+    #[test]
+    fn test_short_bank() {
+        assert_eq!(
+            part2("987654321111111\n12345"),
+            Err(SubsequenceError::TooShort { len: 5, k: 12 })
+        );
+    }
+
+    #[test]
+    fn test_joltage_beyond_u64() {
+        let bank = "9".repeat(40);
+        let expected: Answer = ("1".to_string() + &"9".repeat(29) + "8").parse().unwrap();
+        assert_eq!(
+            total_joltage(&format!("{}\n{}", bank, bank), 30),
+            Ok(expected)
+        );
     }
 }
@@ -1,54 +1,78 @@
-use crate::{Grid, read_input};
+use crate::visualize::{self, Frame};
+use crate::{Grid, Options, read_input};
 
-pub fn solve() {
+pub fn solve(options: &Options) {
     let input = read_input(4);
 
+    visualize::run(options, || frames(&input));
+
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
 }
 
 fn part1(input: &str) -> i32 {
     let grid: Grid<char> = Grid::parse(input);
-    let mut accessible_rolls = 0;
-    for x in 0..grid.width {
-        for y in 0..grid.height {
-            if *grid.get(x, y).unwrap() != '@' {
-                continue;
-            }
-            if count_adjacent_rolls(&grid, x, y) < 4 {
-                accessible_rolls += 1;
-            }
-        }
-    }
-    accessible_rolls
+    removable_rolls(&grid).len() as i32
 }
 
 fn part2(input: &str) -> i32 {
-    let mut grid: Grid<char> = Grid::parse(input);
-    let mut accessible_rolls = 0;
-    loop {
-        let mut removed = Vec::<(usize, usize)>::new();
+    removal_rounds(Grid::parse(input))
+        .map(|grid| removed(&grid) as i32)
+        .sum()
+}
 
-        for x in 0..grid.width {
-            for y in 0..grid.height {
-                if *grid.get(x, y).unwrap() != '@' {
-                    continue;
-                }
-                if count_adjacent_rolls(&grid, x, y) < 4 {
-                    removed.push((x, y));
-                }
-            }
+/// The grid after each round of removals, until no roll can be removed.
+/// Rolls removed in that round are marked `x`, earlier ones are cleared to `.`.
+fn removal_rounds(mut grid: Grid<char>) -> impl Iterator<Item = Grid<char>> {
+    std::iter::from_fn(move || {
+        let removable = removable_rolls(&grid);
+        if removable.is_empty() {
+            return None;
+        }
+        for c in grid.data.iter_mut().filter(|c| **c == 'x') {
+            *c = '.';
         }
-        if removed.is_empty() {
-            break;
-        } else {
-            accessible_rolls += removed.len() as i32;
-            for (x, y) in removed {
-                grid.set(x, y, 'x');
+        for (x, y) in removable {
+            grid.set(x, y, 'x');
+        }
+        Some(grid.clone())
+    })
+}
+
+/// Rolls removed in the round that produced `grid`.
+fn removed(grid: &Grid<char>) -> usize {
+    grid.data.iter().filter(|&&c| c == 'x').count()
+}
+
+/// The grid before any removals, then after each round.
+pub(crate) fn frames(input: &str) -> Vec<Frame> {
+    let grid: Grid<char> = Grid::parse(input);
+    let rolls = grid.data.iter().filter(|&&c| c == '@').count();
+    let mut frames = vec![Frame::new(
+        grid.clone(),
+        format!("Initial: {} rolls", rolls),
+    )];
+    for (i, grid) in removal_rounds(grid).enumerate() {
+        let caption = format!("Round {}: removed {} rolls", i + 1, removed(&grid));
+        frames.push(Frame::new(grid, caption));
+    }
+    frames
+}
+
+/// Rolls with fewer than four rolls around them.
+fn removable_rolls(grid: &Grid<char>) -> Vec<(usize, usize)> {
+    let mut accessible = Vec::new();
+    for x in 0..grid.width {
+        for y in 0..grid.height {
+            if *grid.get(x, y).unwrap() != '@' {
+                continue;
+            }
+            if count_adjacent_rolls(grid, x, y) < 4 {
+                accessible.push((x, y));
             }
         }
     }
-    accessible_rolls
+    accessible
 }
 
 fn count_adjacent_rolls(grid: &Grid<char>, x: usize, y: usize) -> usize {
@@ -80,4 +104,18 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(EXAMPLE), 43);
     }
+
+    #[test]
+    fn test_frames() {
+        let text: Vec<String> = frames("@@@\n@@@\n@@@").iter().map(Frame::to_text).collect();
+        assert_eq!(
+            text,
+            [
+                "Initial: 9 rolls\n@@@\n@@@\n@@@\n",
+                "Round 1: removed 4 rolls\nx@x\n@@@\nx@x\n",
+                "Round 2: removed 4 rolls\n.x.\nx@x\n.x.\n",
+                "Round 3: removed 1 rolls\n...\n.x.\n...\n",
+            ]
+        );
+    }
 }
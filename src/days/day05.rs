@@ -1,95 +1,43 @@
-use crate::{parse_numbers, read_input};
+use crate::interval_set::IntervalSet;
+use crate::{Options, parse_numbers, read_input};
 use std::ops::RangeInclusive;
 
-pub fn solve() {
+pub fn solve(_options: &Options) {
     let input = read_input(5);
 
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
 }
 
-fn part1(input: &str) -> u64 {
-    let (ranges, ids): (Vec<RangeInclusive<u64>>, Vec<u64>) = {
-        let (rs, ids) = input.split_once("\n\n").unwrap();
-        (
-            rs.lines()
-                .map(|s| {
-                    let (start, end) = s.split_once('-').unwrap();
-                    RangeInclusive::new(start.parse().unwrap(), end.parse().unwrap())
-                })
-                .collect(),
-            parse_numbers(ids),
-        )
-    };
-    let mut fresh = 0;
-    for id in ids {
-        for range in &ranges {
-            if range.contains(&id) {
-                fresh += 1;
-                break;
-            }
-        }
-    }
-    fresh
+fn part1(input: &str) -> usize {
+    let (fresh, ids) = parse(input);
+    ids.iter().filter(|id| fresh.contains(id)).count()
 }
-fn part2(input: &str) -> u64 {
-    let mut ranges = Vec::new();
-    let (rs, _) = input.split_once("\n\n").unwrap();
-    for line in rs.lines() {
-        let (start, end) = line.split_once('-').unwrap();
-        ranges.push(RangeElem::Open(start.parse().unwrap()));
-        ranges.push(RangeElem::Close(end.parse().unwrap()));
-    }
-
-    ranges.sort_by(|a, b| {
-        let av = match a {
-            RangeElem::Open(v) | RangeElem::Close(v) => v,
-        };
-        let bv = match b {
-            RangeElem::Open(v) | RangeElem::Close(v) => v,
-        };
-
-        av.cmp(bv).then_with(|| match (a, b) {
-            (RangeElem::Open(_), RangeElem::Close(_)) => std::cmp::Ordering::Less,
-            (RangeElem::Close(_), RangeElem::Open(_)) => std::cmp::Ordering::Greater,
-            _ => std::cmp::Ordering::Equal,
-        })
-    });
-
-    let mut opens = 0;
-    let mut trimmed = Vec::new();
-    for range in ranges {
-        match range {
-            RangeElem::Open(v) => {
-                if 0 == opens {
-                    trimmed.push(v);
-                };
-                opens += 1;
-            }
-            RangeElem::Close(v) => {
-                if 1 == opens {
-                    trimmed.push(v);
-                };
-                opens -= 1;
-            }
-        }
-    }
 
-    trimmed
-        .chunks_exact(2)
-        .map(|pair| pair[1] - pair[0] + 1)
-        .sum()
+/// Counted in a `u128`, since ranges covering every `u64` hold 2⁶⁴ ids.
+fn part2(input: &str) -> u128 {
+    let (fresh, _) = parse(input);
+    fresh.len()
 }
 
-#[derive(Debug)]
-enum RangeElem {
-    Open(u64),
-    Close(u64),
+/// Merge the fresh ranges once so each id lookup is a binary search.
+fn parse(input: &str) -> (IntervalSet<u64>, Vec<u64>) {
+    let (rs, ids) = input.split_once("\n\n").unwrap();
+    let fresh = rs
+        .lines()
+        .map(|line| {
+            let (start, end) = line.split_once('-').unwrap();
+            RangeInclusive::new(start.parse().unwrap(), end.parse().unwrap())
+        })
+        .collect();
+
+    (fresh, parse_numbers(ids))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::xorshift;
 
     const EXAMPLE: &str = "\
 3-5
@@ -112,5 +60,66 @@ mod tests {
     #[test]
     fn test_part2() {
         assert_eq!(part2(EXAMPLE), 14);
+        let everything = format!("0-5\n3-{}\n\n0", u64::MAX);
+        assert_eq!(part2(&everything), 1 << 64);
+    }
+
+    fn large_input(num_ranges: usize, num_ids: usize) -> String {
+        let mut state = 0x2025_0005;
+        let mut input = String::new();
+        for _ in 0..num_ranges {
+            let start = xorshift(&mut state) % 1_000_000_000_000;
+            let len = xorshift(&mut state) % 1_000_000;
+            input.push_str(&format!("{}-{}\n", start, start + len));
+        }
+        input.push('\n');
+        for _ in 0..num_ids {
+            let id = xorshift(&mut state) % 1_000_000_000_000;
+            input.push_str(&format!("{}\n", id));
+        }
+        input
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release day05 -- --ignored --nocapture`"]
+    fn bench_part1_large_input() {
+        use std::time::Instant;
+
+        let input = large_input(100_000, 1_000_000);
+
+        let start = Instant::now();
+        let fresh = part1(&input);
+        let merged = start.elapsed();
+
+        // The old linear scan is O(ids x ranges), so only time it on a sample
+        const SAMPLE: usize = 1_000;
+        let (rs, ids) = input.split_once("\n\n").unwrap();
+        let ranges: Vec<RangeInclusive<u64>> = rs
+            .lines()
+            .map(|line| {
+                let (start, end) = line.split_once('-').unwrap();
+                RangeInclusive::new(start.parse().unwrap(), end.parse().unwrap())
+            })
+            .collect();
+        let ids: Vec<u64> = parse_numbers(ids);
+
+        let start = Instant::now();
+        let naive = ids[..SAMPLE]
+            .iter()
+            .filter(|id| ranges.iter().any(|r| r.contains(id)))
+            .count();
+        let scan = start.elapsed();
+
+        let (set, _) = parse(&input);
+        let sample = ids[..SAMPLE].iter().filter(|id| set.contains(id)).count();
+        assert_eq!(sample, naive);
+
+        println!("merged + binary search: {} fresh in {:?}", fresh, merged);
+        println!(
+            "linear scan: {:?} for {} ids, ~{:?} extrapolated",
+            scan,
+            SAMPLE,
+            scan * (ids.len() / SAMPLE) as u32
+        );
     }
 }
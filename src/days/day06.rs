@@ -1,106 +1,337 @@
-use crate::{Grid, read_input};
+use crate::answer::Answer;
+use crate::{Grid, Options, read_input};
+use std::ops::Range;
+use std::str::FromStr;
 
-pub fn solve() {
+pub fn solve(_options: &Options) {
     let input = read_input(6);
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    match (part1(&input), part2(&input)) {
+        (Ok(p1), Ok(p2)) => {
+            println!("Part 1: {}", p1);
+            println!("Part 2: {}", p2);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("Invalid input: {}", e),
+    }
 }
 
-fn part1(input: &str) -> u64 {
-    let (numbers, operations): (Grid<u64>, Vec<Op>) = parse(input);
-    let mut answer;
-    let mut grand_total = 0;
-
-    for (i, operation) in operations.iter().enumerate() {
-        match operation {
-            Op::Add => {
-                answer = 0;
-                for j in 0..numbers.height {
-                    answer += numbers.get(i, j).unwrap();
-                }
-            }
-            Op::Mul => {
-                answer = 1;
-                for j in 0..numbers.height {
-                    answer *= numbers.get(i, j).unwrap();
-                }
-            }
+fn part1(input: &str) -> Result<Answer, WorksheetError> {
+    let worksheet: Worksheet = input.parse()?;
+    worksheet
+        .problems
+        .iter()
+        .map(|p| p.evaluate(p.row_operands()))
+        .sum()
+}
+
+fn part2(input: &str) -> Result<Answer, WorksheetError> {
+    let worksheet: Worksheet = input.parse()?;
+    worksheet
+        .problems
+        .iter()
+        .map(Problem::evaluate_columns)
+        .sum()
+}
+
+/// Problems laid out side by side, separated by columns that are blank on every line.
+#[derive(Debug)]
+pub struct Worksheet {
+    pub problems: Vec<Problem>,
+}
+
+/// One problem: the operand cells it spans on each row, and the operator below them.
+#[derive(Debug)]
+pub struct Problem {
+    /// Character columns covered by the problem
+    pub columns: Range<usize>,
+    /// The text of each operand row within `columns`, alignment spaces included
+    pub cells: Vec<String>,
+    pub op: &'static Operator,
+}
+
+impl Problem {
+    /// Operands read as one number per row, top to bottom.
+    pub fn row_operands(&self) -> Vec<Answer> {
+        self.cells
+            .iter()
+            .map(|cell| cell.trim().parse().unwrap())
+            .collect()
+    }
+
+    /// Operands read as one number per column, top to bottom, starting from the rightmost column.
+    pub fn column_operands(&self) -> Vec<Answer> {
+        (0..self.columns.len())
+            .rev()
+            .map(|x| {
+                self.cells
+                    .iter()
+                    .filter_map(|cell| cell[x..=x].trim().chars().next())
+                    .collect::<String>()
+            })
+            // Columns under a wide operator such as `min` can be blank
+            .filter(|digits| !digits.is_empty())
+            .map(|digits| digits.parse().unwrap())
+            .collect()
+    }
+
+    /// Evaluate the column operands. Only associative operators are allowed:
+    /// reading right to left is a convention, and `-` or `/` would give a
+    /// different answer grouped the other way.
+    pub fn evaluate_columns(&self) -> Result<Answer, WorksheetError> {
+        if !self.op.associative {
+            return Err(WorksheetError::NonAssociative {
+                column: self.columns.start + 1,
+                symbol: self.op.symbol,
+            });
         }
+        self.evaluate(self.column_operands())
+    }
+
+    /// Fold the operands left to right in the order given.
+    pub fn evaluate(&self, operands: Vec<Answer>) -> Result<Answer, WorksheetError> {
+        let undefined = || WorksheetError::Undefined {
+            column: self.columns.start + 1,
+            symbol: self.op.symbol,
+        };
 
-        grand_total += answer;
+        let mut operands = operands.into_iter();
+        let first = match operands.next() {
+            Some(first) => first,
+            None => return self.op.identity.clone().ok_or_else(undefined),
+        };
+        operands.try_fold(first, |acc, v| (self.op.fold)(acc, v).ok_or_else(undefined))
     }
-    grand_total
 }
 
-fn neutral(op: &Option<Op>) -> u64 {
-    match op {
-        Some(Op::Mul) => 1,
-        _ => 0,
+/// A worksheet that could not be parsed or evaluated. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorksheetError {
+    /// Fewer than an operand row and an operator row
+    TooShort,
+    /// A line whose length differs from the first line
+    RaggedLine {
+        line: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// A character other than a digit or space in an operand row
+    InvalidCell { line: usize, column: usize, c: char },
+    /// An operand row with no number, or digits split by spaces, within a problem
+    BadOperand { line: usize, column: usize },
+    /// A problem with no operator, or more than one, below it
+    BadOperator { column: usize },
+    /// An operator symbol that is not in [`OPERATORS`]
+    UnknownOperator { column: usize, symbol: String },
+    /// The operator has no result for these operands, e.g. division by zero
+    Undefined { column: usize, symbol: &'static str },
+    /// A non-associative operator over operands read column by column
+    NonAssociative { column: usize, symbol: &'static str },
+}
+
+impl std::fmt::Display for WorksheetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WorksheetError::TooShort => write!(f, "worksheet needs operand and operator rows"),
+            WorksheetError::RaggedLine {
+                line,
+                len,
+                expected,
+            } => write!(
+                f,
+                "line {} is {} characters wide, expected {}",
+                line, len, expected
+            ),
+            WorksheetError::InvalidCell { line, column, c } => {
+                write!(f, "line {}, column {}: unexpected {:?}", line, column, c)
+            }
+            WorksheetError::BadOperand { line, column } => write!(
+                f,
+                "line {}, column {}: expected a single number in the problem",
+                line, column
+            ),
+            WorksheetError::BadOperator { column } => write!(
+                f,
+                "column {}: expected a single operator below the problem",
+                column
+            ),
+            WorksheetError::UnknownOperator { column, symbol } => {
+                let known: Vec<&str> = OPERATORS.iter().map(|o| o.symbol).collect();
+                write!(
+                    f,
+                    "column {}: unknown operator {:?}, expected one of {}",
+                    column,
+                    symbol,
+                    known.join(" ")
+                )
+            }
+            WorksheetError::Undefined { column, symbol } => write!(
+                f,
+                "column {}: {:?} is undefined for these operands",
+                column, symbol
+            ),
+            WorksheetError::NonAssociative { column, symbol } => write!(
+                f,
+                "column {}: {:?} is not associative, so column operands have no agreed grouping",
+                column, symbol
+            ),
+        }
     }
 }
 
-fn part2(input: &str) -> u64 {
-    let g: Grid<char> = Grid::parse(input).transpose();
+impl std::error::Error for WorksheetError {}
 
-    let mut transposed = String::with_capacity(g.width * (g.height + 1));
+impl FromStr for Worksheet {
+    type Err = WorksheetError;
 
-    for y in 0..g.height {
-        for x in 0..(g.width - 1) {
-            transposed.push(*g.get(x, y).unwrap());
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.trim_end_matches('\n').lines().collect();
+        if lines.len() < 2 {
+            return Err(WorksheetError::TooShort);
         }
-        transposed.push('\n');
-    }
-    let ops: String = g.col(g.width - 1).cloned().collect();
-    let mut operations = ops.split_whitespace().map(|s| match s {
-        "+" => Op::Add,
-        "*" => Op::Mul,
-        _ => panic!("bad op"),
-    });
-
-    let mut current_op = operations.next();
-    let mut answer = neutral(&current_op);
-
-    let mut grand_total = 0;
-
-    for line in transposed.lines() {
-        let trimmed = line.trim();
-
-        if trimmed.is_empty() {
-            grand_total += answer;
-            current_op = operations.next();
-            answer = neutral(&current_op)
-        } else {
-            match current_op {
-                Some(Op::Add) => {
-                    answer += trimmed.parse::<u64>().unwrap();
-                }
-                Some(Op::Mul) => {
-                    answer *= trimmed.parse::<u64>().unwrap();
+
+        // Alignment is what carries meaning here, so lines must not be padded or trimmed
+        let expected = lines[0].chars().count();
+        for (i, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            if len != expected {
+                return Err(WorksheetError::RaggedLine {
+                    line: i + 1,
+                    len,
+                    expected,
+                });
+            }
+        }
+
+        let grid: Grid<char> = Grid::parse(&lines.join("\n"));
+        let operator_row = grid.height - 1;
+
+        for y in 0..operator_row {
+            for x in 0..grid.width {
+                let &c = grid.get(x, y).unwrap();
+                if c != ' ' && !c.is_ascii_digit() {
+                    return Err(WorksheetError::InvalidCell {
+                        line: y + 1,
+                        column: x + 1,
+                        c,
+                    });
                 }
-                _ => panic!("bad op"),
             }
         }
+
+        let mut problems = Vec::new();
+        let mut x = 0;
+        while x < grid.width {
+            if grid.col(x).all(|&c| c == ' ') {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < grid.width && !grid.col(x).all(|&c| c == ' ') {
+                x += 1;
+            }
+            problems.push(parse_problem(&grid, start..x)?);
+        }
+
+        Ok(Worksheet { problems })
     }
-    answer + grand_total
 }
 
-fn parse(s: &str) -> (Grid<u64>, Vec<Op>) {
-    let (ns, ops): (&str, &str) = s.trim().rsplit_once("\n").unwrap();
-    let operations = ops.split_whitespace().map(|s| match s {
-        "+" => Op::Add,
-        "*" => Op::Mul,
-        _ => panic!("bad op"),
-    });
-    (Grid::parse_u64s(ns), operations.collect())
+fn parse_problem(grid: &Grid<char>, columns: Range<usize>) -> Result<Problem, WorksheetError> {
+    let row = |y: usize| -> String { columns.clone().map(|x| grid.get(x, y).unwrap()).collect() };
+    let operator_row = grid.height - 1;
+
+    let cells: Vec<String> = (0..operator_row).map(row).collect();
+    for (y, cell) in cells.iter().enumerate() {
+        let number = cell.trim();
+        if number.is_empty() || number.contains(' ') {
+            return Err(WorksheetError::BadOperand {
+                line: y + 1,
+                column: columns.start + 1,
+            });
+        }
+    }
+
+    let operators = row(operator_row);
+    let mut symbols = operators.split_whitespace();
+    let symbol = match (symbols.next(), symbols.next()) {
+        (Some(symbol), None) => symbol,
+        _ => {
+            return Err(WorksheetError::BadOperator {
+                column: columns.start + 1,
+            });
+        }
+    };
+    let op = OPERATORS
+        .iter()
+        .find(|o| o.symbol == symbol)
+        .ok_or_else(|| WorksheetError::UnknownOperator {
+            column: columns.start
+                + operators[..operators.find(symbol).unwrap()].chars().count()
+                + 1,
+            symbol: symbol.to_string(),
+        })?;
+
+    Ok(Problem { columns, cells, op })
 }
 
+/// An operator that can appear below a problem.
 #[derive(Debug)]
-enum Op {
-    Add,
-    Mul,
+pub struct Operator {
+    pub symbol: &'static str,
+    /// Result for a problem without operands, if the operator has one
+    pub identity: Option<Answer>,
+    /// Combine the running result with the next operand, `None` if undefined
+    pub fold: fn(Answer, Answer) -> Option<Answer>,
+    /// Whether grouping is irrelevant. Non-associative operators depend on
+    /// the left-to-right order the operands are read in.
+    pub associative: bool,
 }
 
+pub const OPERATORS: [Operator; 7] = [
+    Operator {
+        symbol: "+",
+        identity: Some(Answer::Int(0)),
+        fold: |a, b| Some(a + b),
+        associative: true,
+    },
+    Operator {
+        symbol: "*",
+        identity: Some(Answer::Int(1)),
+        fold: |a, b| Some(a * b),
+        associative: true,
+    },
+    Operator {
+        symbol: "-",
+        identity: None,
+        fold: |a, b| Some(a - b),
+        associative: false,
+    },
+    Operator {
+        symbol: "/",
+        identity: None,
+        fold: Answer::checked_div,
+        associative: false,
+    },
+    Operator {
+        symbol: "min",
+        identity: None,
+        fold: |a, b| Some(a.min(b)),
+        associative: true,
+    },
+    Operator {
+        symbol: "max",
+        identity: None,
+        fold: |a, b| Some(a.max(b)),
+        associative: true,
+    },
+    Operator {
+        symbol: "||",
+        identity: None,
+        fold: |a, b| format!("{}{}", a, b).parse().ok(),
+        associative: true,
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,11 +344,145 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 4277556);
+        assert_eq!(part1(EXAMPLE), Ok(4277556.into()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 3263827);
+        assert_eq!(part2(EXAMPLE), Ok(3263827.into()));
+    }
+
+    #[test]
+    fn test_product_beyond_u64() {
+        let input = "\
+18446744073709551615 9
+18446744073709551615 9
+*                    +";
+        let expected: Answer = "340282366920938463426481119284349108225".parse().unwrap();
+        assert_eq!(part1(input), Ok(expected + 18.into()));
+    }
+
+    #[test]
+    fn test_worksheet_layout() {
+        let worksheet: Worksheet = EXAMPLE.parse().unwrap();
+        let spans: Vec<Range<usize>> = worksheet
+            .problems
+            .iter()
+            .map(|p| p.columns.clone())
+            .collect();
+        assert_eq!(spans, vec![0..3, 4..7, 8..11, 12..15]);
+
+        let last = &worksheet.problems[3];
+        assert_eq!(last.op.symbol, "+");
+        assert_eq!(last.cells, vec!["64 ", "23 ", "314"]);
+        assert_eq!(last.row_operands(), vec![64.into(), 23.into(), 314.into()]);
+        assert_eq!(
+            last.column_operands(),
+            vec![4.into(), 431.into(), 623.into()]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let ragged = "123 328\n 45 64\n*   +  ";
+        assert_eq!(
+            part1(ragged),
+            Err(WorksheetError::RaggedLine {
+                line: 2,
+                len: 6,
+                expected: 7
+            })
+        );
+
+        let bad_cell = "123 3x8\n 45 64 \n*   +  ";
+        assert_eq!(
+            part2(bad_cell),
+            Err(WorksheetError::InvalidCell {
+                line: 1,
+                column: 6,
+                c: 'x'
+            })
+        );
+
+        let no_operator = "123 328\n 45 64 \n*      ";
+        assert_eq!(
+            part1(no_operator),
+            Err(WorksheetError::BadOperator { column: 5 })
+        );
+    }
+
+    #[test]
+    fn test_extended_operators() {
+        let input = "\
+20 100 7   12  12 7 
+ 5  10 3    5   5 3 
+ 2   5 9   30  30 42
+-  /   min max || ||";
+        let worksheet: Worksheet = input.parse().unwrap();
+        let rows: Vec<Answer> = worksheet
+            .problems
+            .iter()
+            .map(|p| p.evaluate(p.row_operands()).unwrap())
+            .collect();
+        let columns: Vec<Result<Answer, WorksheetError>> = worksheet
+            .problems
+            .iter()
+            .map(Problem::evaluate_columns)
+            .collect();
+
+        assert_eq!(rows, [13, 2, 3, 30, 12530, 7342].map(Answer::from).to_vec());
+        assert_eq!(
+            columns,
+            [
+                Err(WorksheetError::NonAssociative {
+                    column: 1,
+                    symbol: "-"
+                }),
+                Err(WorksheetError::NonAssociative {
+                    column: 4,
+                    symbol: "/"
+                }),
+                Ok(739.into()),
+                Ok(250.into()),
+                Ok(25013.into()),
+                Ok(2734.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operator_errors() {
+        let unknown = "12 34\n 5  6\n+  ^ ";
+        assert_eq!(
+            part1(unknown),
+            Err(WorksheetError::UnknownOperator {
+                column: 4,
+                symbol: "^".to_string()
+            })
+        );
+        assert!(
+            part1(unknown)
+                .unwrap_err()
+                .to_string()
+                .contains("+ * - / min max ||")
+        );
+
+        // Columns count characters, not bytes, after a wide space
+        assert_eq!(
+            part1("12\n 5\n\u{3000}×"),
+            Err(WorksheetError::UnknownOperator {
+                column: 2,
+                symbol: "×".to_string()
+            })
+        );
+
+        let divide_by_zero = "12\n 0\n/ ";
+        assert_eq!(
+            part1(divide_by_zero),
+            Err(WorksheetError::Undefined {
+                column: 1,
+                symbol: "/"
+            })
+        );
     }
 }
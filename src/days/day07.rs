@@ -1,104 +1,466 @@
-use std::collections::HashMap;
+use crate::answer::Answer;
+use crate::visualize::{self, Frame};
+use crate::{Grid, Options, read_input};
+use std::str::FromStr;
 
-use crate::read_input;
+pub fn solve(options: &Options) {
+    // `--manifold <file>` simulates a custom manifold instead of the puzzle input
+    if let Some(path) = options.value::<String>("--manifold") {
+        let input = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read manifold {}: {}", path, e));
+        match input.parse::<Manifold>() {
+            Ok(manifold) => {
+                let run = manifold.simulate();
+                println!("Splitters hit: {}", run.splitters_hit);
+                println!("Energized cells: {}", run.energized);
+                match run.timelines {
+                    Some(timelines) => println!("Timelines: {}", timelines),
+                    None => println!("Timelines: unbounded, beams loop forever"),
+                }
+            }
+            Err(e) => println!("Invalid manifold: {}", e),
+        }
+        return;
+    }
 
-pub fn solve() {
     let input = read_input(7);
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    if let Ok(manifold) = parse(&input) {
+        visualize::run(options, || frames(&manifold));
+    }
+
+    match (part1(&input), part2(&input)) {
+        (Ok(p1), Ok(p2)) => {
+            println!("Part 1: {}", p1);
+            println!("Part 2: {}", p2);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("Invalid input: {}", e),
+    }
+}
+
+fn part1(input: &str) -> Result<usize, ManifoldError> {
+    Ok(sweep(&parse(input)?, |_, _| {}).splitters_hit)
 }
 
-fn part1(input: &str) -> usize {
-    let mut bm: BeamMap = parse(input);
-    tachyon_beam(&mut bm.manifold, bm.start, bm.height);
-    bm.manifold.into_iter().filter(|(_, x)| *x == 1).count()
+fn part2(input: &str) -> Result<Answer, ManifoldError> {
+    Ok(sweep(&parse(input)?, |_, _| {}).timelines)
 }
 
-fn part2(input: &str) -> usize {
-    let mut bm: BeamMap = parse(input);
-    quantum_tachyon_beam(&mut bm.manifold, bm.start, bm.height)
+/// A manifold from the puzzle, which holds only `S`, `^` and empty space.
+fn parse(input: &str) -> Result<Manifold, ManifoldError> {
+    let manifold: Manifold = input.parse()?;
+    let grid = &manifold.grid;
+    match grid
+        .data
+        .iter()
+        .position(|c| !matches!(c, Cell::Empty | Cell::Start | Cell::Splitter))
+    {
+        Some(i) => Err(ManifoldError::NotInPuzzle {
+            line: i / grid.width + 1,
+            column: i % grid.width + 1,
+        }),
+        None => Ok(manifold),
+    }
 }
 
-fn tachyon_beam(
-    manifold: &mut HashMap<(usize, usize), usize>,
-    start: (usize, usize),
-    height: usize,
-) {
-    for y in start.1..height {
-        match manifold.get(&(start.0, y)) {
-            Some(0) => {
-                manifold.insert((start.0, y), 1);
-                if start.0 > 0 {
-                    tachyon_beam(manifold, (start.0 - 1, y + 1), height);
+struct BeamReport {
+    /// Distinct splitters reached by at least one beam
+    splitters_hit: usize,
+    /// Ways a single particle can travel from `S` out of the manifold
+    timelines: Answer,
+}
+
+/// Push the beams down one row at a time, tracking how many timelines put a
+/// beam in each column. A splitter stops its beam and starts one in each
+/// neighbouring column on the next row. Beams split off the side of the
+/// manifold leave it there, ending their timelines.
+///
+/// `on_row` sees the timeline counts of the beams entering each row.
+fn sweep<F>(manifold: &Manifold, mut on_row: F) -> BeamReport
+where
+    F: FnMut(usize, &[Answer]),
+{
+    let grid = &manifold.grid;
+    let (start_x, start_y) = manifold.start;
+
+    let mut beams = vec![Answer::default(); grid.width];
+    beams[start_x] = Answer::from(1);
+    let mut splitters_hit = 0;
+    let mut exited = Answer::default();
+
+    for y in start_y..grid.height {
+        on_row(y, &beams);
+        let mut next = vec![Answer::default(); grid.width];
+        for (x, count) in beams.into_iter().enumerate() {
+            if count == Answer::default() {
+                continue;
+            }
+            if grid.get(x, y) != Some(&Cell::Splitter) {
+                next[x] += count;
+                continue;
+            }
+
+            splitters_hit += 1;
+            for nx in [x.checked_sub(1), Some(x + 1)] {
+                match nx {
+                    Some(nx) if nx < grid.width => next[nx] += count.clone(),
+                    _ => exited += count.clone(),
                 }
-                tachyon_beam(manifold, (start.0 + 1, y + 1), height);
-                return;
             }
-            Some(_) => {
-                return;
+        }
+        beams = next;
+    }
+
+    BeamReport {
+        splitters_hit,
+        timelines: beams.into_iter().sum::<Answer>() + exited,
+    }
+}
+
+/// The manifold after each row of the sweep, with beams drawn as `|` in the
+/// style of the puzzle: split beams appear beside their splitter.
+pub(crate) fn frames(manifold: &Manifold) -> Vec<Frame> {
+    let grid = &manifold.grid;
+    let mut drawn = Grid {
+        width: grid.width,
+        height: grid.height,
+        data: grid
+            .data
+            .iter()
+            .map(|c| match c {
+                Cell::Start => 'S',
+                Cell::Splitter => '^',
+                _ => '.',
+            })
+            .collect(),
+    };
+    let mut frames = Vec::new();
+    sweep(manifold, |y, beams| {
+        for (x, count) in beams.iter().enumerate() {
+            if *count == Answer::default() {
+                continue;
+            }
+            let cells = if grid.get(x, y) == Some(&Cell::Splitter) {
+                [x.checked_sub(1), Some(x + 1)]
+            } else {
+                [Some(x), None]
+            };
+            for cx in cells.into_iter().flatten() {
+                if drawn.get(cx, y) == Some(&'.') {
+                    drawn.set(cx, y, '|');
+                }
             }
-            None => {}
+        }
+        frames.push(Frame::new(drawn.clone(), format!("Row {}", y + 1)));
+    });
+    frames
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Dir::Up => (0, -1),
+            Dir::Down => (0, 1),
+            Dir::Left => (-1, 0),
+            Dir::Right => (1, 0),
+        }
+    }
+
+    fn perpendicular(self) -> [Dir; 2] {
+        match self {
+            Dir::Up | Dir::Down => [Dir::Left, Dir::Right],
+            Dir::Left | Dir::Right => [Dir::Up, Dir::Down],
         }
     }
 }
-fn quantum_tachyon_beam(
-    manifold: &mut HashMap<(usize, usize), usize>,
-    start: (usize, usize),
-    height: usize,
-) -> usize {
-    for y in start.1..height {
-        match manifold.get(&(start.0, y)) {
-            Some(0) => {
-                let worlds = quantum_tachyon_beam(manifold, (start.0 - 1, y + 1), height)
-                    + quantum_tachyon_beam(manifold, (start.0 + 1, y + 1), height);
-                manifold.insert((start.0, y), worlds);
-                return worlds;
+
+/// A component of a manifold. The puzzle uses only `S`, `^` and empty space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    /// `.` or an already drawn beam `|` / `-`
+    Empty,
+    /// `S`, where the beam enters moving down
+    Start,
+    /// `^` stops the beam and starts one on each side of it, one step further on
+    Splitter,
+    /// `/` reflects right-moving beams up, down-moving beams left, and so on
+    MirrorSlash,
+    /// `\` reflects right-moving beams down, down-moving beams right, and so on
+    MirrorBackslash,
+    /// `#` ends any beam that reaches it
+    Absorber,
+    /// `<` or `>` turns every beam to move left or right
+    Deflector(Dir),
+}
+
+/// A malformed manifold, with the 1-based position of the problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifoldError {
+    Empty,
+    /// A line whose width differs from the first line
+    RaggedLine {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownCell {
+        line: usize,
+        column: usize,
+        c: char,
+    },
+    DuplicateStart {
+        line: usize,
+        column: usize,
+    },
+    NoStart,
+    /// A component other than `S`, `^` and empty space, outside `--manifold`
+    NotInPuzzle {
+        line: usize,
+        column: usize,
+    },
+}
+
+impl std::fmt::Display for ManifoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ManifoldError::UnknownCell { line, column, c } => {
+                write!(
+                    f,
+                    "line {}, column {}: unknown component {:?}",
+                    line, column, c
+                )
             }
-            Some(x) => {
-                return *x;
+            ManifoldError::Empty => write!(f, "manifold is empty"),
+            ManifoldError::RaggedLine {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} is {} characters wide, expected {}",
+                line, found, expected
+            ),
+            ManifoldError::DuplicateStart { line, column } => {
+                write!(f, "line {}, column {}: second S", line, column)
             }
-            None => {}
+            ManifoldError::NoStart => write!(f, "manifold has no S"),
+            ManifoldError::NotInPuzzle { line, column } => write!(
+                f,
+                "line {}, column {}: only S, ^ and . are allowed without --manifold",
+                line, column
+            ),
         }
     }
-    1
 }
-struct BeamMap {
-    manifold: HashMap<(usize, usize), usize>,
+
+impl std::error::Error for ManifoldError {}
+
+pub struct Manifold {
+    grid: Grid<Cell>,
     start: (usize, usize),
-    height: usize,
 }
 
-fn parse(s: &str) -> BeamMap {
-    let mut manifold = HashMap::new();
-    let mut start = (0, 0);
-    let mut height = 0;
+/// What happened to every beam started from `S`.
+#[derive(Debug)]
+pub struct Simulation {
+    /// Distinct splitters reached by at least one beam
+    pub splitters_hit: usize,
+    /// Distinct cells a beam passed through
+    pub energized: usize,
+    /// Ways a single particle can travel from `S` until it leaves the manifold
+    /// or is absorbed. `None` if a beam can loop forever.
+    pub timelines: Option<Answer>,
+}
+
+impl FromStr for Manifold {
+    type Err = ManifoldError;
 
-    for (y, line) in s.lines().enumerate() {
-        height += 1;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Grid::parse asserts on these, so catch them first
+        let lines: Vec<&str> = s.lines().collect();
+        let expected = lines.first().map_or(0, |l| l.chars().count());
+        if expected == 0 {
+            return Err(ManifoldError::Empty);
+        }
+        for (i, line) in lines.iter().enumerate() {
+            let found = line.chars().count();
+            if found != expected {
+                return Err(ManifoldError::RaggedLine {
+                    line: i + 1,
+                    expected,
+                    found,
+                });
+            }
+        }
 
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '^' => {
-                    manifold.insert((x, y), 0);
+        let chars: Grid<char> = Grid::parse(s);
+        let mut data = Vec::with_capacity(chars.data.len());
+        let mut start = None;
+
+        for (i, &c) in chars.data.iter().enumerate() {
+            let (x, y) = (i % chars.width, i / chars.width);
+            data.push(match c {
+                '.' | '|' | '-' => Cell::Empty,
+                'S' if start.is_none() => {
+                    start = Some((x, y));
+                    Cell::Start
                 }
                 'S' => {
-                    start = (x, y);
+                    return Err(ManifoldError::DuplicateStart {
+                        line: y + 1,
+                        column: x + 1,
+                    });
                 }
-                _ => {}
-            }
+                '^' => Cell::Splitter,
+                '/' => Cell::MirrorSlash,
+                '\\' => Cell::MirrorBackslash,
+                '#' => Cell::Absorber,
+                '<' => Cell::Deflector(Dir::Left),
+                '>' => Cell::Deflector(Dir::Right),
+                _ => {
+                    return Err(ManifoldError::UnknownCell {
+                        line: y + 1,
+                        column: x + 1,
+                        c,
+                    });
+                }
+            });
+        }
+
+        Ok(Manifold {
+            grid: Grid {
+                width: chars.width,
+                height: chars.height,
+                data,
+            },
+            start: start.ok_or(ManifoldError::NoStart)?,
+        })
+    }
+}
+
+/// Where a beam goes after passing through a cell.
+enum Next {
+    Beam(usize, usize, Dir),
+    /// Left the manifold or was absorbed
+    End,
+}
+
+impl Manifold {
+    fn state(&self, x: usize, y: usize, dir: Dir) -> usize {
+        self.grid.index(x, y) * 4 + dir as usize
+    }
+
+    fn step(&self, x: usize, y: usize, moves: &[Dir], dir: Dir) -> Next {
+        let (mut nx, mut ny) = (x as isize, y as isize);
+        for m in moves {
+            let (dx, dy) = m.offset();
+            nx += dx;
+            ny += dy;
+        }
+        if nx >= 0 && ny >= 0 && self.grid.in_bounds(nx as usize, ny as usize) {
+            Next::Beam(nx as usize, ny as usize, dir)
+        } else {
+            Next::End
         }
     }
-    BeamMap {
-        manifold,
-        start,
-        height,
+
+    /// Where a beam in cell (x, y) moving in `dir` continues.
+    fn next(&self, x: usize, y: usize, dir: Dir) -> Vec<Next> {
+        let turn = |d: Dir| vec![self.step(x, y, &[d], d)];
+        match self.grid.get(x, y).unwrap() {
+            Cell::Empty | Cell::Start => turn(dir),
+            Cell::Splitter => dir
+                .perpendicular()
+                .map(|side| self.step(x, y, &[side, dir], dir))
+                .into(),
+            Cell::MirrorSlash => turn(match dir {
+                Dir::Up => Dir::Right,
+                Dir::Right => Dir::Up,
+                Dir::Down => Dir::Left,
+                Dir::Left => Dir::Down,
+            }),
+            Cell::MirrorBackslash => turn(match dir {
+                Dir::Up => Dir::Left,
+                Dir::Left => Dir::Up,
+                Dir::Down => Dir::Right,
+                Dir::Right => Dir::Down,
+            }),
+            Cell::Absorber => vec![Next::End],
+            Cell::Deflector(d) => turn(*d),
+        }
+    }
+
+    /// Trace every beam from `S` with an explicit stack, counting timelines
+    /// bottom-up and detecting beams that revisit a state they came from.
+    pub fn simulate(&self) -> Simulation {
+        const UNSEEN: u8 = 0;
+        const ACTIVE: u8 = 1;
+        const DONE: u8 = 2;
+
+        let states = self.grid.data.len() * 4;
+        let mut status = vec![UNSEEN; states];
+        let mut timelines = vec![Answer::default(); states];
+        let mut energized = vec![false; self.grid.data.len()];
+        let mut cyclic = false;
+
+        let (sx, sy) = self.start;
+        let mut stack = vec![(sx, sy, Dir::Down, false)];
+        while let Some((x, y, dir, expanded)) = stack.pop() {
+            let state = self.state(x, y, dir);
+            if expanded {
+                timelines[state] = self
+                    .next(x, y, dir)
+                    .into_iter()
+                    .map(|n| match n {
+                        Next::Beam(nx, ny, nd) => timelines[self.state(nx, ny, nd)].clone(),
+                        Next::End => Answer::from(1),
+                    })
+                    .sum();
+                status[state] = DONE;
+                continue;
+            }
+            if status[state] != UNSEEN {
+                continue;
+            }
+
+            status[state] = ACTIVE;
+            energized[self.grid.index(x, y)] = true;
+            stack.push((x, y, dir, true));
+            for n in self.next(x, y, dir) {
+                if let Next::Beam(nx, ny, nd) = n {
+                    match status[self.state(nx, ny, nd)] {
+                        UNSEEN => stack.push((nx, ny, nd, false)),
+                        ACTIVE => cyclic = true,
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let splitters_hit = (0..self.grid.data.len())
+            .filter(|&i| energized[i] && self.grid.data[i] == Cell::Splitter)
+            .count();
+
+        Simulation {
+            splitters_hit,
+            energized: energized.iter().filter(|&&e| e).count(),
+            timelines: (!cyclic).then(|| timelines[self.state(sx, sy, Dir::Down)].clone()),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::xorshift;
 
     const EXAMPLE: &str = "\
 .......S.......
@@ -120,11 +482,180 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 21);
+        assert_eq!(part1(EXAMPLE), Ok(21));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 40);
+        assert_eq!(part2(EXAMPLE), Ok(40.into()));
+    }
+
+    #[test]
+    fn test_splitters_at_edges() {
+        let manifold = "\
+S..^
+^...
+...^";
+        // The left split leaves the manifold
+        assert_eq!(part1(manifold), Ok(1));
+        assert_eq!(part2(manifold), Ok(2.into()));
+    }
+
+    #[test]
+    fn test_tall_manifold() {
+        let mut manifold = String::from("..S..\n");
+        for _ in 0..100_000 {
+            manifold.push_str(".....\n");
+        }
+        manifold.push_str("..^..");
+        assert_eq!(part1(&manifold), Ok(1));
+        assert_eq!(part2(&manifold), Ok(2.into()));
+    }
+
+    #[test]
+    fn test_timelines_beyond_u128() {
+        let mut manifold = String::from("....S....\n");
+        for i in 0..300 {
+            manifold.push_str(if i % 2 == 0 {
+                "^.^.^.^.^\n"
+            } else {
+                ".^.^.^.^.\n"
+            });
+        }
+        assert!(part2(&manifold).unwrap() > Answer::from(u128::MAX));
+    }
+
+    #[test]
+    fn test_simulation_matches_sweep() {
+        let run = EXAMPLE.parse::<Manifold>().unwrap().simulate();
+        assert_eq!(Ok(run.splitters_hit), part1(EXAMPLE));
+        assert_eq!(run.timelines, part2(EXAMPLE).ok());
+
+        let mut state = 0x2025_0007;
+        for _ in 0..200 {
+            let width = 1 + (xorshift(&mut state) % 12) as usize;
+            let height = 1 + (xorshift(&mut state) % 12) as usize;
+            let mut manifold: Vec<char> = (0..width * height)
+                .map(|_| {
+                    if xorshift(&mut state).is_multiple_of(3) {
+                        '^'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            manifold[(xorshift(&mut state) as usize) % width] = 'S';
+            let input: Vec<String> = manifold
+                .chunks(width)
+                .map(|row| row.iter().collect())
+                .collect();
+            let input = input.join("\n");
+
+            let run = input.parse::<Manifold>().unwrap().simulate();
+            assert_eq!(Ok(run.splitters_hit), part1(&input), "{}", input);
+            assert_eq!(run.timelines, part2(&input).ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_frames() {
+        let frames = frames(&parse(EXAMPLE).unwrap());
+        assert_eq!(frames.len(), 16);
+        assert!(frames[2].to_text().starts_with(
+            "Row 3\n.......S.......\n.......|.......\n......|^|......\n...............\n"
+        ));
+        assert_eq!(
+            frames.last().unwrap().to_text(),
+            "\
+Row 16
+.......S.......
+.......|.......
+......|^|......
+......|.|......
+.....|^|^|.....
+.....|.|.|.....
+....|^|^|^|....
+....|.|.|.|....
+...|^|^|||^|...
+...|.|.|||.|...
+..|^|^|||^|^|..
+..|.|.|||.|.|..
+.|^|||^||.||^|.
+.|.|||.||.||.|.
+|^|^|^|^|^|||^|
+|.|.|.|.|.|||.|
+"
+        );
+    }
+
+    #[test]
+    fn test_mirrors_and_deflectors() {
+        let manifold = "\
+S..<.
+\\.^..
+...#.";
+        // The mirror sends the beam right into a splitter: the upper half is
+        // turned back by `<` and leaves on the left, the lower half is absorbed
+        let run = manifold.parse::<Manifold>().unwrap().simulate();
+        assert_eq!(run.timelines, Some(2.into()));
+        assert_eq!(run.splitters_hit, 1);
+        assert_eq!(run.energized, 8);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let manifold = "\
+S..
+>.\\
+...
+\\./";
+        let run = manifold.parse::<Manifold>().unwrap().simulate();
+        assert_eq!(run.timelines, None);
+        assert_eq!(run.energized, 9);
+    }
+
+    #[test]
+    fn test_unknown_component() {
+        assert_eq!(
+            "S.\n.x".parse::<Manifold>().err(),
+            Some(ManifoldError::UnknownCell {
+                line: 2,
+                column: 2,
+                c: 'x'
+            })
+        );
+        assert_eq!(
+            ".S.\n.^S".parse::<Manifold>().err(),
+            Some(ManifoldError::DuplicateStart { line: 2, column: 3 })
+        );
+        assert_eq!(
+            "S..\n..\n...".parse::<Manifold>().err(),
+            Some(ManifoldError::RaggedLine {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!("".parse::<Manifold>().err(), Some(ManifoldError::Empty));
+        assert_eq!("\nS".parse::<Manifold>().err(), Some(ManifoldError::Empty));
+        assert_eq!(
+            "..\n..".parse::<Manifold>().err(),
+            Some(ManifoldError::NoStart)
+        );
+
+        // The puzzle parts return the same errors instead of panicking
+        assert_eq!(part1("..\n.."), Err(ManifoldError::NoStart));
+        assert_eq!(
+            part2("S.\n^^^"),
+            Err(ManifoldError::RaggedLine {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            part1("S.\n/."),
+            Err(ManifoldError::NotInPuzzle { line: 2, column: 1 })
+        );
     }
 }
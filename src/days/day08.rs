@@ -1,107 +1,258 @@
-use crate::read_input;
-use std::{collections::HashMap, str::FromStr};
+use crate::kdtree::KdTree;
+use crate::mst::{SpanningTree, UnionFind, boruvka, prim};
+use crate::point::{ParsePointError, Point};
+use crate::{Options, read_input};
+use std::collections::BTreeMap;
 
-pub fn solve() {
+pub fn solve(options: &Options) {
     let input = read_input(8);
 
-    println!("Part 1: {}", part1(&input, 1000));
-    println!("Part 2: {}", part2(&input));
-}
-
-fn part1(input: &str, num_shortest: usize) -> usize {
-    let points = parse(input);
-    let distances: Vec<(usize, usize, f64)> = build_distances(&points);
-    let mut circuits: Vec<usize> = (0..points.len()).collect();
+    if let Some(path) = options.value::<String>("--json")
+        && let Ok(points) = parse(&input)
+    {
+        let json = Circuits::connect(&points, CONNECTIONS).to_json();
+        std::fs::write(&path, json).unwrap_or_else(|e| panic!("Failed to write {}: {}", path, e));
+    }
 
-    for (i, j, _) in distances.iter().take(num_shortest) {
-        let ci = find_circuit_id(&mut circuits, *i);
-        let cj = find_circuit_id(&mut circuits, *j);
-        if ci != cj {
-            circuits[cj] = ci
+    match (part1(&input, CONNECTIONS), part2(&input)) {
+        (Ok(p1), Ok(p2)) => {
+            println!("Part 1: {}", p1);
+            println!("Part 2: {}", p2);
         }
+        (Err(e), _) | (_, Err(e)) => println!("Invalid input: {}", e),
     }
+}
 
-    let mut counts: HashMap<usize, usize> = HashMap::new();
-    for i in 0..circuits.len() {
-        let root = find_circuit_id(&mut circuits, i);
-        *counts.entry(root).or_insert(0) += 1;
-    }
+/// How many of the closest pairs part 1 connects.
+const CONNECTIONS: usize = 1000;
 
-    // Sort by count descending and take top 3
-    let mut count_vec: Vec<_> = counts.into_iter().collect();
-    count_vec.sort_by(|a, b| b.1.cmp(&a.1));
-    count_vec.iter().take(3).map(|(_, count)| count).product()
+fn part1(input: &str, num_shortest: usize) -> Result<usize, JunctionBoxError> {
+    let circuits = Circuits::connect(&parse(input)?, num_shortest);
+    Ok(circuits.members.iter().take(3).map(Vec::len).product())
 }
 
-fn part2(input: &str) -> i64 {
-    let points = parse(input);
-    let distances: Vec<(usize, usize, f64)> = build_distances(&points);
-    let mut circuits: Vec<usize> = (0..points.len()).collect();
-    let mut num_circuits = points.len();
-
-    for (i, j, _) in distances {
-        let ci = find_circuit_id(&mut circuits, i);
-        let cj = find_circuit_id(&mut circuits, j);
-        if ci != cj {
-            circuits[cj] = ci;
-            num_circuits -= 1;
-            if num_circuits == 1 {
-                return (points[i].x as i64) * (points[j].x as i64);
-            }
-        }
-    }
+fn part2(input: &str) -> Result<i64, JunctionBoxError> {
+    let points = parse(input)?;
+    // Connecting pairs in order, the last merge is the spanning tree's heaviest edge
+    let (i, j, _) = spanning_tree(&points)
+        .heaviest_edge()
+        .ok_or(JunctionBoxError::TooFewBoxes(points.len()))?;
+    Ok(points[i].x() * points[j].x())
+}
 
-    unreachable!();
+/// One of the closest pairs, connected in turn.
+#[derive(Debug, PartialEq, Eq)]
+struct Step {
+    a: usize,
+    b: usize,
+    distance_squared: i128,
+    /// False if the boxes were already in the same circuit
+    merged: bool,
+    /// How many circuits there are of each size afterwards, largest first
+    sizes: Vec<(usize, usize)>,
 }
+
+/// How the circuits form as the closest pairs are connected.
 #[derive(Debug)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
+struct Circuits {
+    boxes: Vec<Point<3>>,
+    steps: Vec<Step>,
+    /// The boxes in each circuit at the end, largest circuit first
+    members: Vec<Vec<usize>>,
 }
 
-impl FromStr for Point {
-    type Err = Box<dyn std::error::Error>;
+impl Circuits {
+    fn connect(points: &[Point<3>], connections: usize) -> Self {
+        let tree = index(points);
+        let mut sets = UnionFind::new(points.len());
+        // Number of circuits of each size
+        let mut sizes = BTreeMap::new();
+        if !points.is_empty() {
+            sizes.insert(1, points.len());
+        }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(',').collect();
-        Ok(Point {
-            x: parts[0].parse()?,
-            y: parts[1].parse()?,
-            z: parts[2].parse()?,
-        })
+        let mut steps = Vec::new();
+        for (a, b, distance_squared) in tree.closest_pairs().take(connections) {
+            let before = (sets.size(a), sets.size(b));
+            let merged = sets.union(a, b);
+            if merged {
+                for size in [before.0, before.1] {
+                    let count = sizes.get_mut(&size).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        sizes.remove(&size);
+                    }
+                }
+                *sizes.entry(before.0 + before.1).or_insert(0) += 1;
+            }
+            steps.push(Step {
+                a,
+                b,
+                distance_squared,
+                merged,
+                sizes: sizes.iter().rev().map(|(&s, &n)| (s, n)).collect(),
+            });
+        }
+
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+        for i in 0..points.len() {
+            members[sets.find(i)].push(i);
+        }
+        members.retain(|m| !m.is_empty());
+        // Each list is already in order, so ties fall back to the lowest box
+        members.sort_by(|x, y| y.len().cmp(&x.len()).then(x[0].cmp(&y[0])));
+
+        Circuits {
+            boxes: points.to_vec(),
+            steps,
+            members,
+        }
     }
-}
-impl Point {
-    fn distance_to(&self, other: &Point) -> f64 {
-        let dx = (self.x - other.x) as f64;
-        let dy = (self.y - other.y) as f64;
-        let dz = (self.z - other.z) as f64;
-        (dx * dx + dy * dy + dz * dz).sqrt()
+
+    /// The boxes, the final circuits and every step, with boxes numbered
+    /// from 0 in input order and steps from 1.
+    fn to_json(&self) -> String {
+        let list = |items: Vec<String>| format!("[{}]", items.join(", "));
+        let boxes: Vec<String> = self
+            .boxes
+            .iter()
+            .map(|b| format!("    {}", list(b.0.iter().map(i64::to_string).collect())))
+            .collect();
+        let circuits: Vec<String> = self
+            .members
+            .iter()
+            .map(|m| format!("    {}", list(m.iter().map(usize::to_string).collect())))
+            .collect();
+        let steps: Vec<String> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let sizes = step.sizes.iter().map(|(s, n)| format!("[{}, {}]", s, n));
+                format!(
+                    "    {{\"step\": {}, \"boxes\": [{}, {}], \"distance_squared\": {}, \"merged\": {}, \"sizes\": {}}}",
+                    i + 1,
+                    step.a,
+                    step.b,
+                    step.distance_squared,
+                    step.merged,
+                    list(sizes.collect())
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"boxes\": [\n{}\n  ],\n  \"circuits\": [\n{}\n  ],\n  \"steps\": [\n{}\n  ]\n}}\n",
+            boxes.join(",\n"),
+            circuits.join(",\n"),
+            steps.join(",\n")
+        )
     }
 }
 
-fn parse(s: &str) -> Vec<Point> {
-    s.lines().map(|l| l.parse().unwrap()).collect()
+/// A junction box that could not be read, with its 1-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JunctionBoxError {
+    InvalidPoint {
+        line: usize,
+        error: ParsePointError,
+    },
+    /// A coordinate outside `i32`, where squared distances and the part 2
+    /// product could overflow
+    OutOfRange {
+        line: usize,
+        coordinate: i64,
+    },
+    /// Part 2 needs a pair of boxes to connect
+    TooFewBoxes(usize),
 }
 
-fn build_distances(points: &[Point]) -> Vec<(usize, usize, f64)> {
-    let mut distances = Vec::new();
-    for i in 0..points.len() {
-        for j in (i + 1)..points.len() {
-            distances.push((i, j, points[i].distance_to(&points[j])));
+impl std::fmt::Display for JunctionBoxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            JunctionBoxError::InvalidPoint { line, error } => write!(f, "line {}: {}", line, error),
+            JunctionBoxError::OutOfRange { line, coordinate } => write!(
+                f,
+                "line {}: coordinate {} is outside {}..={}",
+                line,
+                coordinate,
+                i32::MIN,
+                i32::MAX
+            ),
+            JunctionBoxError::TooFewBoxes(n) => {
+                write!(f, "need at least 2 junction boxes to connect, got {}", n)
+            }
         }
     }
-    distances.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
-    distances
 }
 
-fn find_circuit_id(circuits: &mut [usize], i: usize) -> usize {
-    if circuits[i] != i {
-        circuits[i] = find_circuit_id(circuits, circuits[i]);
+impl std::error::Error for JunctionBoxError {}
+
+fn parse(s: &str) -> Result<Vec<Point<3>>, JunctionBoxError> {
+    s.lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let line = i + 1;
+            let point: Point<3> = l
+                .parse()
+                .map_err(|error| JunctionBoxError::InvalidPoint { line, error })?;
+            match point
+                .coords()
+                .into_iter()
+                .find(|&c| i32::try_from(c).is_err())
+            {
+                Some(coordinate) => Err(JunctionBoxError::OutOfRange { line, coordinate }),
+                None => Ok(point),
+            }
+        })
+        .collect()
+}
+
+/// Pairs come out of the tree nearest first as they are needed, rather
+/// than sorting all n² of them up front. Distances are compared exactly as
+/// squared integers, and pairs the same distance apart are taken in order of
+/// their line indices `(i, j)`, so "the N shortest" is always the same set.
+fn index(points: &[Point<3>]) -> KdTree<3> {
+    KdTree::new(points.to_vec())
+}
+
+/// Edge weights carry the pair's indices, so ties break the same way as in
+/// [`index`] and both algorithms find the same tree.
+type Weight = (i128, usize, usize);
+
+/// Above this many junction boxes Borůvka's algorithm on the k-d tree beats
+/// Prim's algorithm visiting all n² pairs. They break even at around 1500
+/// in release builds on boxes scattered uniformly through a 100000-wide
+/// cube: Prim took 13ms against 17ms at 1000 boxes, and 51ms against 47ms
+/// at 2000.
+const PRIM_LIMIT: usize = 1500;
+
+fn spanning_tree(points: &[Point<3>]) -> SpanningTree<Weight> {
+    if points.len() <= PRIM_LIMIT {
+        prim_spanning_tree(points)
+    } else {
+        boruvka_spanning_tree(points)
     }
-    circuits[i]
 }
+
+fn prim_spanning_tree(points: &[Point<3>]) -> SpanningTree<Weight> {
+    prim(points.len(), |i, j| {
+        (points[i].distance_squared(&points[j]), i.min(j), i.max(j))
+    })
+}
+
+/// Each round asks the k-d tree for every box's nearest box in another
+/// circuit, so a far outlier costs one more round rather than n² pairs.
+fn boruvka_spanning_tree(points: &[Point<3>]) -> SpanningTree<Weight> {
+    let tree = index(points);
+    boruvka(points.len(), |circuit| {
+        tree.nearest_in_other_group(circuit)
+            .into_iter()
+            .enumerate()
+            .map(|(i, nearest)| nearest.map(|(d, j)| (j, (d, i.min(j), i.max(j)))))
+            .collect()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,25 +281,146 @@ mod tests {
 
     #[test]
     fn test_calc_distance() {
-        let p1 = Point {
-            x: 162,
-            y: 817,
-            z: 812,
-        };
-        let p2 = Point {
-            x: 425,
-            y: 690,
-            z: 689,
-        };
-        assert_eq!(p1.distance_to(&p2), 316.90219311326956);
+        let p1 = Point([162, 817, 812]);
+        let p2 = Point([425, 690, 689]);
+        let d = p1.distance_squared(&p2);
+        assert_eq!(d, 100427);
+        assert_eq!((d as f64).sqrt(), 316.90219311326956);
     }
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE, 10), 40);
+        assert_eq!(part1(EXAMPLE, 10), Ok(40));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 25272);
+        assert_eq!(part2(EXAMPLE), Ok(25272));
+    }
+
+    #[test]
+    fn test_circuits() {
+        let circuits = Circuits::connect(&parse(EXAMPLE).unwrap(), 10);
+        let sizes: Vec<usize> = circuits.members.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [5, 4, 2, 2, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(circuits.members[0], [2, 8, 13, 17, 18]);
+        assert_eq!(circuits.members[1], [0, 7, 14, 19]);
+        assert_eq!(sizes.iter().sum::<usize>(), 20);
+
+        // The closest pair from the puzzle text comes first
+        let first = &circuits.steps[0];
+        assert_eq!((first.a, first.b, first.merged), (0, 19, true));
+        assert_eq!(first.sizes, [(2, 1), (1, 18)]);
+        let unmerged: Vec<usize> = (0..10).filter(|&i| !circuits.steps[i].merged).collect();
+        assert_eq!(unmerged, [3]);
+        assert_eq!(circuits.steps[9].sizes, [(5, 1), (4, 1), (2, 2), (1, 7)]);
+    }
+
+    #[test]
+    fn test_circuits_json() {
+        let json = Circuits::connect(&parse("0,0,0\n1,0,0\n5,0,0").unwrap(), 2).to_json();
+        assert_eq!(
+            json,
+            "{
+  \"boxes\": [
+    [0, 0, 0],
+    [1, 0, 0],
+    [5, 0, 0]
+  ],
+  \"circuits\": [
+    [0, 1, 2]
+  ],
+  \"steps\": [
+    {\"step\": 1, \"boxes\": [0, 1], \"distance_squared\": 1, \"merged\": true, \"sizes\": [[2, 1], [1, 1]]},
+    {\"step\": 2, \"boxes\": [1, 2], \"distance_squared\": 16, \"merged\": true, \"sizes\": [[3, 1]]}
+  ]
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_invalid_junction_boxes() {
+        assert_eq!(
+            part1("0,0,0\n1,2", 1),
+            Err(JunctionBoxError::InvalidPoint {
+                line: 2,
+                error: ParsePointError::WrongDimensions {
+                    expected: 3,
+                    found: 2
+                }
+            })
+        );
+        // Squared distances between these would overflow an i128
+        let far = format!("{},0,0\n{},0,0", i64::MIN, i64::MAX);
+        assert_eq!(
+            part2(&far),
+            Err(JunctionBoxError::OutOfRange {
+                line: 1,
+                coordinate: i64::MIN
+            })
+        );
+        let edge = format!("{},0,0\n{},0,0", i32::MIN, i32::MAX);
+        assert_eq!(part2(&edge), Ok(i32::MIN as i64 * i32::MAX as i64));
+    }
+
+    #[test]
+    fn test_too_few_boxes() {
+        assert_eq!(part2(""), Err(JunctionBoxError::TooFewBoxes(0)));
+        assert_eq!(part2("1,2,3"), Err(JunctionBoxError::TooFewBoxes(1)));
+    }
+
+    #[test]
+    fn test_tied_distances() {
+        // (0, 1), (1, 2) and (3, 4) are all 1 apart; the first two are taken
+        let line = "0,0,0\n1,0,0\n2,0,0\n10,0,0\n11,0,0";
+        assert_eq!(part1(line, 2), Ok(3));
+        assert_eq!(part1(line, 3), Ok(3 * 2));
+
+        // Every side of the square is 1 long; (1, 3) joins the last corner before (2, 3)
+        let square = "0,0,0\n1,0,0\n0,1,0\n1,1,0";
+        assert_eq!(part2(square), Ok(1));
+        let square = "0,1,0\n1,1,0\n0,0,0\n1,0,0";
+        assert_eq!(part2(square), Ok(1));
+        let square = "1,0,0\n0,0,0\n1,1,0\n0,1,0";
+        assert_eq!(part2(square), Ok(0));
+    }
+
+    #[test]
+    fn test_outlier_above_prim_limit() {
+        let mut state = 0x2025_0848;
+        let mut points: Vec<Point<3>> = (0..PRIM_LIMIT + 100)
+            .map(|_| Point([(); 3].map(|_| (crate::xorshift(&mut state) % 100000) as i64)))
+            .collect();
+        let outlier = points.len();
+        points.push(Point([10_000_000; 3]));
+
+        // The outlier joins last, along its shortest edge
+        let (i, j, (d, _, _)) = spanning_tree(&points).heaviest_edge().unwrap();
+        assert!(i == outlier || j == outlier);
+        let shortest = points[..outlier]
+            .iter()
+            .map(|p| p.distance_squared(&points[outlier]))
+            .min();
+        assert_eq!(Some(d), shortest);
+    }
+
+    #[test]
+    fn test_spanning_trees_agree() {
+        let mut state = 0x2025_0847;
+        for _ in 0..20 {
+            let n = 2 + (crate::xorshift(&mut state) % 60) as usize;
+            let points: Vec<Point<3>> = (0..n)
+                .map(|_| Point([(); 3].map(|_| (crate::xorshift(&mut state) % 8) as i64)))
+                .collect();
+            let prim = prim_spanning_tree(&points);
+            let boruvka = boruvka_spanning_tree(&points);
+            assert_eq!(
+                prim.heaviest_edge().map(|e| e.2),
+                boruvka.heaviest_edge().map(|e| e.2)
+            );
+            let total =
+                |tree: &SpanningTree<Weight>| tree.edges().iter().map(|e| e.2.0).sum::<i128>();
+            assert_eq!(total(&prim), total(&boruvka));
+        }
     }
 }
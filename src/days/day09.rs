@@ -1,157 +1,413 @@
-use crate::read_input;
-use std::collections::HashMap;
+use crate::point::Point;
+use crate::polygon::{PolygonError, RectilinearPolygon, TileIndex};
+use crate::visualize::{self, Frame};
+use crate::{Grid, Options, read_input};
+use std::cmp::Reverse;
+use std::collections::HashSet;
 
-pub fn solve() {
+pub fn solve(options: &Options) {
     let input = read_input(9);
+    let polygon = parse(&input);
 
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    if let Ok(polygon) = &polygon {
+        visualize::run(options, || frames(polygon));
+        if let Some(path) = options.value::<String>("--svg") {
+            let image = svg(polygon, options.flag("--svg-rejected"));
+            std::fs::write(&path, image)
+                .unwrap_or_else(|e| panic!("Failed to write {}: {}", path, e));
+        }
+    }
+
+    match (part1(&input), part2(&input)) {
+        (Ok(p1), Ok(p2)) => {
+            println!("Part 1: {}", p1);
+            println!("Part 2: {}", p2);
+        }
+        (Err(e), _) | (_, Err(e)) => println!("Invalid input: {}", e),
+    }
+
+    // Related questions about the same floor; `--top <k>` lists the best rectangles
+    if let Ok(polygon) = &polygon {
+        let rectangles = Rectangles::new(polygon);
+        if let Some((a, b)) = rectangles.largest_anywhere() {
+            println!("Largest rectangle anywhere: {}", rectangle_size(a, b));
+        }
+        println!("Valid corner pairs: {}", rectangles.valid_pairs().count());
+        if let Some(k) = options.value::<usize>("--top") {
+            println!("Top {} rectangles:", k);
+            for (a, b) in rectangles.top(k) {
+                println!(
+                    "  {},{} to {},{}: {}",
+                    a.x(),
+                    a.y(),
+                    b.x(),
+                    b.y(),
+                    rectangle_size(a, b)
+                );
+            }
+        }
+    }
+}
+
+fn part1(input: &str) -> Result<u64, InputError> {
+    let (a, b) = largest(&parse(input)?);
+    Ok(rectangle_size(a, b))
 }
 
-fn part1(input: &str) -> usize {
-    let red_points = parse(input);
+fn part2(input: &str) -> Result<u64, InputError> {
+    let polygon = parse(input)?;
+    let best = Rectangles::new(&polygon).largest_inside(|_, _| {});
+    Ok(best.map_or(0, |(a, b)| rectangle_size(a, b)))
+}
+
+/// Opposite corners of the largest rectangle between two red tiles.
+fn largest(polygon: &RectilinearPolygon) -> (Point<2>, Point<2>) {
+    let red_points = polygon.vertices();
     red_points
         .iter()
         .enumerate()
-        .flat_map(|(i, &a)| {
-            red_points[i + 1..]
-                .iter()
-                .map(move |&b| rectangle_size(a, b))
-        })
-        .max()
+        .flat_map(|(i, &a)| red_points[i + 1..].iter().map(move |&b| (a, b)))
+        .max_by_key(|&(a, b)| rectangle_size(a, b))
         .unwrap()
 }
 
-fn part2(input: &str) -> usize {
-    let mut red_points = parse(input);
-    let (rows, min_y) = preprocess(&red_points);
+/// Questions about rectangles made entirely of red and green tiles, sharing
+/// one compressed index of the floor.
+struct Rectangles<'a> {
+    polygon: &'a RectilinearPolygon,
+    index: TileIndex,
+    /// Red tiles sorted by x, with their cells in `index`
+    red: Vec<(Point<2>, (usize, usize))>,
+}
 
-    red_points.sort_unstable_by_key(|p| p.0);
-    let max_h = rows.len();
-    let mut best = 0;
+impl<'a> Rectangles<'a> {
+    fn new(polygon: &'a RectilinearPolygon) -> Self {
+        let index = polygon.tile_index();
+        let mut red: Vec<(Point<2>, (usize, usize))> = polygon
+            .vertices()
+            .iter()
+            .map(|&p| (p, index.cell(p).unwrap()))
+            .collect();
+        red.sort_unstable_by_key(|(p, _)| p.x());
+        Rectangles {
+            polygon,
+            index,
+            red,
+        }
+    }
 
-    for (i, &a) in red_points.iter().enumerate() {
-        for &b in red_points[i + 1..].iter().rev() {
-            let width = b.0 - a.0 + 1;
-            if width * max_h <= best {
-                break;
-            }
-            let area = rectangle_size(a, b);
-            if area > best && is_inside(a, b, &rows, min_y) {
-                best = area;
+    /// Like `largest`, but only rectangles inside the polygon. `on_rejected`
+    /// sees each candidate that would have been the best so far but left it.
+    fn largest_inside<F>(&self, mut on_rejected: F) -> Option<(Point<2>, Point<2>)>
+    where
+        F: FnMut(Point<2>, Point<2>),
+    {
+        let (Point([_, min_y]), Point([_, max_y])) = self.polygon.bounds();
+        let max_h = min_y.abs_diff(max_y) + 1;
+        let mut best = 0;
+        let mut corners = None;
+
+        for (i, &(a, cell_a)) in self.red.iter().enumerate() {
+            for &(b, cell_b) in self.red[i + 1..].iter().rev() {
+                let width = a.x().abs_diff(b.x()) + 1;
+                if width * max_h <= best {
+                    break;
+                }
+                let area = rectangle_size(a, b);
+                if area <= best {
+                    continue;
+                }
+                if self.index.contains_cells(cell_a, cell_b) {
+                    best = area;
+                    corners = Some((a, b));
+                } else {
+                    on_rejected(a, b);
+                }
             }
         }
+
+        corners
     }
 
-    best
-}
+    /// Every pair of red tiles whose rectangle is inside the polygon.
+    fn valid_pairs(&self) -> impl Iterator<Item = (Point<2>, Point<2>)> + '_ {
+        self.red
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, &(a, cell_a))| {
+                self.red[i + 1..]
+                    .iter()
+                    .filter(move |&&(_, cell_b)| self.index.contains_cells(cell_a, cell_b))
+                    .map(move |&(b, _)| (a.min(b), a.max(b)))
+            })
+    }
 
-fn parse(s: &str) -> Vec<(usize, usize)> {
-    s.lines()
-        .map(|l| {
-            let (a, b) = l.split_once(',').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
-        })
-        .collect()
-}
+    /// The `k` largest rectangles inside the polygon with red corners,
+    /// largest first and then by corners.
+    fn top(&self, k: usize) -> Vec<(Point<2>, Point<2>)> {
+        let mut pairs: Vec<(Point<2>, Point<2>)> = self.valid_pairs().collect();
+        pairs.sort_unstable_by_key(|&(a, b)| (Reverse(rectangle_size(a, b)), a, b));
+        pairs.truncate(k);
+        pairs
+    }
 
-fn rectangle_size(p1: (usize, usize), p2: (usize, usize)) -> usize {
-    let x = p1.0.abs_diff(p2.0) + 1;
-    let y = p1.1.abs_diff(p2.1) + 1;
-    x * y
+    /// The largest rectangle inside the polygon, whether or not its corners are red.
+    fn largest_anywhere(&self) -> Option<(Point<2>, Point<2>)> {
+        self.index.largest_rectangle()
+    }
 }
 
-fn preprocess(poly: &[(usize, usize)]) -> (Vec<Vec<(usize, usize)>>, usize) {
-    let mut intersects: HashMap<usize, Vec<usize>> = HashMap::new();
+/// The tiles, then the answers to both parts with their rectangle marked `O`.
+/// Red tiles are `#` and green tiles `X`, as in the puzzle. Large floors are
+/// compressed to one cell per red tile coordinate and one per gap between them.
+pub(crate) fn frames(polygon: &RectilinearPolygon) -> Vec<Frame> {
+    let red_points = polygon.vertices();
+    let red: HashSet<Point<2>> = red_points.iter().copied().collect();
+    let index = polygon.tile_index();
 
-    for i in 0..poly.len() {
-        let (x1, y1) = poly[i];
-        let (x2, y2) = poly[(i + 1) % poly.len()];
-
-        if x1 == x2 {
-            let y_start = y1.min(y2);
-            let y_end = y1.max(y2);
-            for y in y_start..=y_end {
-                intersects.entry(y).or_default().push(x1);
+    let xs = cell_coordinates(red_points.iter().map(|p| p.x()));
+    let ys = cell_coordinates(red_points.iter().map(|p| p.y()));
+    let draw = |rectangle: Option<(Point<2>, Point<2>)>| {
+        let mut data = Vec::with_capacity(xs.len() * ys.len());
+        for &y in &ys {
+            for &x in &xs {
+                let in_rectangle = rectangle.is_some_and(|(a, b)| {
+                    (a.x().min(b.x())..=a.x().max(b.x())).contains(&x)
+                        && (a.y().min(b.y())..=a.y().max(b.y())).contains(&y)
+                });
+                let p = Point([x, y]);
+                data.push(if red.contains(&p) {
+                    '#'
+                } else if in_rectangle {
+                    'O'
+                } else if index.contains_rectangle(p, p) {
+                    'X'
+                } else {
+                    '.'
+                });
             }
-        } else {
-            intersects.entry(y1).or_default().push(x1);
-            intersects.entry(y1).or_default().push(x2);
         }
-    }
-
-    let min_y = *intersects.keys().min().unwrap_or(&0);
-    let max_y = *intersects.keys().max().unwrap_or(&0);
-    let mut rows = vec![vec![]; max_y - min_y + 1];
+        Grid {
+            width: xs.len(),
+            height: ys.len(),
+            data,
+        }
+    };
 
-    for (y, xs) in intersects {
-        rows[y - min_y] = to_pairs(xs);
+    let part1 = largest(polygon);
+    let mut frames = vec![
+        Frame::new(draw(None), "Tiles"),
+        Frame::new(
+            draw(Some(part1)),
+            format!("Part 1: {}", rectangle_size(part1.0, part1.1)),
+        ),
+    ];
+    if let Some(part2) = Rectangles::new(polygon).largest_inside(|_, _| {}) {
+        let caption = format!("Part 2: {}", rectangle_size(part2.0, part2.1));
+        frames.push(Frame::new(draw(Some(part2)), caption));
     }
-
-    (rows, min_y)
+    frames
 }
 
-fn to_pairs(mut xs: Vec<usize>) -> Vec<(usize, usize)> {
-    if xs.len() < 2 {
-        return vec![];
-    }
-    xs.sort_unstable();
+/// An SVG of the polygon through the red tiles, the tiles themselves and the
+/// rectangles chosen by both parts, optionally with every rectangle part 2
+/// rejected. Each tile is a unit square.
+fn svg(polygon: &RectilinearPolygon, show_rejected: bool) -> String {
+    let rect = |(a, b): (Point<2>, Point<2>), style: &str| {
+        format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>\n",
+            a.x().min(b.x()),
+            a.y().min(b.y()),
+            a.x().abs_diff(b.x()) + 1,
+            a.y().abs_diff(b.y()) + 1,
+            style
+        )
+    };
 
-    let first = xs[0];
-    let last = *xs.last().unwrap();
+    let mut rejected = Vec::new();
+    let part2 = Rectangles::new(polygon).largest_inside(|a, b| rejected.push((a, b)));
+    let part1 = largest(polygon);
 
-    let mut result: Vec<usize> = Vec::new();
-    let mut i = 0;
-    while i < xs.len() {
-        let val = xs[i];
-        let count = xs[i..].iter().take_while(|&&x| x == val).count();
-        if count % 2 != 0 {
-            result.push(val);
+    let red_points = polygon.vertices();
+    let (Point([min_x, min_y]), Point([max_x, max_y])) = polygon.bounds();
+    // Keep outlines and vertices visible however large the floor is
+    let span = (max_x - min_x).max(max_y - min_y) + 1;
+    let stroke = span as f64 / 400.0;
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        min_x as f64 - 1.0,
+        min_y as f64 - 1.0,
+        max_x - min_x + 3,
+        max_y - min_y + 3
+    );
+    let points: Vec<String> = red_points
+        .iter()
+        .map(|p| format!("{},{}", p.x() as f64 + 0.5, p.y() as f64 + 0.5))
+        .collect();
+    out += &format!(
+        "  <polygon points=\"{}\" fill=\"#3caa50\" fill-opacity=\"0.3\" stroke=\"#3caa50\" stroke-width=\"{:.3}\"/>\n",
+        points.join(" "),
+        stroke
+    );
+    if show_rejected {
+        let style = format!(
+            "class=\"rejected\" fill=\"none\" stroke=\"#888888\" stroke-opacity=\"0.3\" stroke-width=\"{:.3}\"",
+            stroke
+        );
+        for &r in &rejected {
+            out += &rect(r, &style);
         }
-        i += count;
     }
-
-    // Make sure first and last are included
-    if result.first() != Some(&first) {
-        result.insert(0, first);
+    out += &rect(
+        part1,
+        &format!(
+            "class=\"part1\" fill=\"none\" stroke=\"#3c78dc\" stroke-width=\"{:.3}\" stroke-dasharray=\"{:.3}\"",
+            stroke * 2.0,
+            stroke * 6.0
+        ),
+    );
+    if let Some(part2) = part2 {
+        out += &rect(
+            part2,
+            &format!(
+                "class=\"part2\" fill=\"#fae664\" fill-opacity=\"0.5\" stroke=\"#c8a000\" stroke-width=\"{:.3}\"",
+                stroke * 2.0
+            ),
+        );
     }
-    if result.last() != Some(&last) {
-        result.push(last);
+    for p in red_points {
+        out += &format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{:.3}\" fill=\"#e62828\"/>\n",
+            p.x() as f64 + 0.5,
+            p.y() as f64 + 0.5,
+            (stroke * 3.0).max(0.3)
+        );
     }
+    out + "</svg>\n"
+}
+
+/// The coordinate each column (or row) of a frame shows, with a one-tile border.
+fn cell_coordinates(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    const MAX_UNCOMPRESSED: i64 = 200;
 
-    result
-        .chunks(2)
-        .filter(|c| c.len() == 2)
-        .map(|c| (c[0], c[1]))
-        .collect()
+    let mut values: Vec<i64> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+    let (min, max) = (values[0], values[values.len() - 1]);
+
+    if max - min <= MAX_UNCOMPRESSED {
+        return (min - 1..=max + 1).collect();
+    }
+    let mut cells = vec![min - 1];
+    for (i, &v) in values.iter().enumerate() {
+        cells.push(v);
+        // Any tile in the gap stands for the whole gap
+        if values.get(i + 1).is_none_or(|&next| next > v + 1) {
+            cells.push(v + 1);
+        }
+    }
+    cells
 }
 
-fn is_inside(
-    p1: (usize, usize),
-    p2: (usize, usize),
-    rows: &[Vec<(usize, usize)>],
-    min_y: usize,
-) -> bool {
-    let x1 = p1.0.min(p2.0);
-    let x2 = p1.0.max(p2.0);
-    let y1 = p1.1.min(p2.1);
-    let y2 = p1.1.max(p2.1);
+/// A problem with the red tiles, with the 1-based lines involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    InvalidPoint {
+        line: usize,
+        text: String,
+    },
+    TooFewPoints(usize),
+    /// The tile on `line` repeats the one before it
+    DuplicatePoint {
+        line: usize,
+    },
+    /// The tiles on lines `from` and `to` share neither a row nor a column
+    DiagonalEdge {
+        from: usize,
+        to: usize,
+    },
+    /// The edges between tiles on these pairs of lines cross or touch
+    SelfIntersection {
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+}
 
-    for y in y1..=y2 {
-        if y < min_y || y - min_y >= rows.len() {
-            return false;
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InputError::InvalidPoint { line, text } => {
+                write!(f, "line {} {:?}: expected x,y", line, text)
+            }
+            InputError::TooFewPoints(n) => {
+                write!(f, "need at least 4 red tiles to enclose an area, got {}", n)
+            }
+            InputError::DuplicatePoint { line } => {
+                write!(f, "line {}: repeats the previous red tile", line)
+            }
+            InputError::DiagonalEdge { from, to } => write!(
+                f,
+                "lines {} and {}: consecutive red tiles must share a row or column",
+                from, to
+            ),
+            InputError::SelfIntersection { first, second } => write!(
+                f,
+                "the edge between lines {} and {} crosses the edge between lines {} and {}",
+                first.0, first.1, second.0, second.1
+            ),
         }
-        let ranges = &rows[y - min_y];
-        if ranges.is_empty() || !ranges.iter().any(|&(sx, ex)| x1 >= sx && x2 <= ex) {
-            return false;
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Parse one `x,y` red tile per line, skipping blank lines, and check that
+/// they trace a simple rectilinear polygon.
+fn parse(input: &str) -> Result<RectilinearPolygon, InputError> {
+    let mut lines = Vec::new();
+    let mut points = Vec::new();
+    for (i, l) in input.lines().enumerate() {
+        if l.trim().is_empty() {
+            continue;
         }
+        let point: Point<2> = l.parse().map_err(|_| InputError::InvalidPoint {
+            line: i + 1,
+            text: l.to_string(),
+        })?;
+        lines.push(i + 1);
+        points.push(point);
     }
-    true
+
+    let n = points.len();
+    let edge = |i: usize| (lines[i], lines[(i + 1) % n]);
+    RectilinearPolygon::new(points).map_err(|e| match e {
+        PolygonError::TooFewVertices(n) => InputError::TooFewPoints(n),
+        PolygonError::DuplicateVertex { index } => {
+            InputError::DuplicatePoint { line: lines[index] }
+        }
+        PolygonError::DiagonalEdge { index } => {
+            let (from, to) = edge(index);
+            InputError::DiagonalEdge { from, to }
+        }
+        PolygonError::SelfIntersection { first, second } => InputError::SelfIntersection {
+            first: edge(first),
+            second: edge(second),
+        },
+    })
+}
+
+fn rectangle_size(p1: Point<2>, p2: Point<2>) -> u64 {
+    let x = p1.x().abs_diff(p2.x()) + 1;
+    let y = p1.y().abs_diff(p2.y()) + 1;
+    x * y
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::polygon::{Boundary, random_polygon};
+    use crate::xorshift;
 
     const EXAMPLE: &str = "\
 7,1
@@ -165,11 +421,195 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(EXAMPLE), 50);
+        assert_eq!(part1(EXAMPLE), Ok(50));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 24);
+        assert_eq!(part2(EXAMPLE), Ok(24));
+    }
+
+    #[test]
+    fn test_frames() {
+        let frames = frames(&parse(EXAMPLE).unwrap());
+        assert_eq!(
+            frames[2].to_text(),
+            "\
+Part 2: 24
+............
+......#XXX#.
+......XXXXX.
+.#OOOO#OOXX.
+.OOOOOOOOXX.
+.#OOOOOO#XX.
+........XXX.
+........#X#.
+............
+"
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let image = svg(&parse(EXAMPLE).unwrap(), false);
+        assert!(
+            image.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"1 0 12 9\">")
+        );
+        assert!(image.contains(
+            "<polygon points=\"7.5,1.5 11.5,1.5 11.5,7.5 9.5,7.5 9.5,5.5 2.5,5.5 2.5,3.5 7.5,3.5\""
+        ));
+        assert!(image.contains("<rect x=\"2\" y=\"3\" width=\"10\" height=\"5\" class=\"part1\""));
+        assert!(image.contains("<rect x=\"2\" y=\"3\" width=\"8\" height=\"3\" class=\"part2\""));
+        assert_eq!(image.matches("<circle").count(), 8);
+        assert!(!image.contains("rejected"));
+
+        // Part 1's rectangle leaves the polygon, so part 2 must have rejected it
+        let image = svg(&parse(EXAMPLE).unwrap(), true);
+        assert!(
+            image.contains("<rect x=\"2\" y=\"3\" width=\"10\" height=\"5\" class=\"rejected\"")
+        );
+    }
+
+    #[test]
+    fn test_compressed_frame() {
+        let input = "1000,1000\n5000,1000\n5000,3000\n1000,3000";
+        assert_eq!(
+            frames(&parse(input).unwrap())[0].to_text(),
+            "Tiles\n.....\n.#X#.\n.XXX.\n.#X#.\n.....\n"
+        );
+    }
+
+    /// Check every tile of every rectangle.
+    fn brute_force_largest_inside(polygon: &RectilinearPolygon) -> u64 {
+        let poly = polygon.vertices();
+        let mut best = 0;
+        for (i, &a) in poly.iter().enumerate() {
+            for &b in &poly[i + 1..] {
+                let inside = (a.y().min(b.y())..=a.y().max(b.y())).all(|y| {
+                    (a.x().min(b.x())..=a.x().max(b.x()))
+                        .all(|x| polygon.contains(Point([x, y]), Boundary::Inclusive))
+                });
+                if inside {
+                    best = best.max(rectangle_size(a, b));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_largest_inside_matches_brute_force() {
+        let mut state = 0x2025_0009;
+        for _ in 0..300 {
+            let columns = 1 + (xorshift(&mut state) % 6) as usize;
+            let scale = 1 + (xorshift(&mut state) % 5) as i64;
+            let polygon = random_polygon(&mut state, columns, scale);
+            assert_eq!(
+                Rectangles::new(&polygon)
+                    .largest_inside(|_, _| {})
+                    .map_or(0, |(a, b)| rectangle_size(a, b)),
+                brute_force_largest_inside(&polygon),
+                "{:?}",
+                polygon
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_rectangle_queries() {
+        let polygon = parse(EXAMPLE).unwrap();
+        let rectangles = Rectangles::new(&polygon);
+
+        let brute_force = (0..8)
+            .flat_map(|i| (i + 1..8).map(move |j| (i, j)))
+            .filter(|&(i, j)| {
+                let (a, b) = (polygon.vertices()[i], polygon.vertices()[j]);
+                (a.y().min(b.y())..=a.y().max(b.y())).all(|y| {
+                    (a.x().min(b.x())..=a.x().max(b.x()))
+                        .all(|x| polygon.contains(Point([x, y]), Boundary::Inclusive))
+                })
+            })
+            .count();
+        assert_eq!(rectangles.valid_pairs().count(), brute_force);
+
+        assert_eq!(brute_force, 16);
+        assert_eq!(
+            rectangles.top(3),
+            [
+                (Point([2, 3]), Point([9, 5])),
+                (Point([9, 7]), Point([11, 1])),
+                (Point([2, 5]), Point([7, 3]))
+            ]
+        );
+        assert_eq!(
+            rectangles.largest_anywhere(),
+            Some((Point([2, 3]), Point([11, 5])))
+        );
+    }
+
+    #[test]
+    fn test_notch_beside_rectangle() {
+        // The previous row-by-row check merged row 11 into one span from x = 2
+        // to 6, counting the notch above y = 7 as inside and missing x = 7..8
+        let input = "2,11\n3,11\n3,7\n6,7\n6,13\n7,13\n7,11\n8,11\n8,1\n7,1\n7,6\n6,6\n6,4\n2,4";
+        let index = parse(input).unwrap().tile_index();
+        assert!(index.contains_rectangle(Point([6, 7]), Point([7, 13])));
+        assert!(!index.contains_rectangle(Point([4, 11]), Point([4, 11])));
+    }
+
+    #[test]
+    fn test_far_apart_coordinates() {
+        // A row per y would need 10^9 rows
+        let corners = [
+            [0, 0],
+            [1_000_000_000, 0],
+            [1_000_000_000, 1_000_000_000],
+            [500_000_000, 1_000_000_000],
+            [500_000_000, 1],
+            [0, 1],
+        ];
+        let polygon = RectilinearPolygon::new(corners.map(Point).to_vec()).unwrap();
+        assert_eq!(
+            Rectangles::new(&polygon).largest_inside(|_, _| {}),
+            Some((
+                Point([500_000_000, 1_000_000_000]),
+                Point([1_000_000_000, 0])
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let err = |input: &str| part2(input).unwrap_err();
+
+        assert_eq!(
+            err("7,1\n11,1\n11,x"),
+            InputError::InvalidPoint {
+                line: 3,
+                text: "11,x".to_string()
+            }
+        );
+        assert_eq!(err("7,1\n11,1\n\n11,7"), InputError::TooFewPoints(3));
+        assert_eq!(
+            err(&EXAMPLE.replace("9,5", "9,7")),
+            InputError::DuplicatePoint { line: 5 }
+        );
+        // 9,5 to 2,4 is diagonal
+        assert_eq!(
+            err(&EXAMPLE.replace("2,5", "2,4")),
+            InputError::DiagonalEdge { from: 5, to: 6 }
+        );
+        // Moving 2,5 and 2,3 past x = 11 makes the edge from 9,5 cross the right side
+        assert_eq!(
+            err(&EXAMPLE.replace("2,5", "13,5").replace("2,3", "13,3")),
+            InputError::SelfIntersection {
+                first: (2, 3),
+                second: (5, 6)
+            }
+        );
+        assert_eq!(
+            err("7,1\n11,1\n11,x").to_string(),
+            "line 3 \"11,x\": expected x,y"
+        );
     }
 }
@@ -1,16 +1,37 @@
 use std::{convert::Infallible, str::FromStr};
 
-use crate::read_input;
+use crate::{Options, read_input};
 
 use good_lp::{
     Expression, ProblemVariables, Solution, SolverModel, Variable, constraint, microlp, variable,
 };
 
-pub fn solve() {
+pub fn solve(options: &Options) {
     let input = read_input(10);
 
     println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    match part2(&input) {
+        Ok(p2) => println!("Part 2: {}", p2),
+        Err(e) => println!("Invalid input: {}", e),
+    }
+
+    // Compare the exact solver with the LP solver on every machine
+    if options.flag("--check-lp") {
+        let machines = parse(&input);
+        let mut mismatches = 0;
+        for (i, machine) in machines.iter().enumerate() {
+            let (exact, lp) = (fewest_presses(machine), lp_presses(machine));
+            if exact != lp {
+                println!("Line {}: exact {:?}, LP {:?}", i + 1, exact, lp);
+                mismatches += 1;
+            }
+        }
+        println!(
+            "{} of {} machines differ from the LP",
+            mismatches,
+            machines.len()
+        );
+    }
 }
 
 fn part1(input: &str) -> usize {
@@ -106,47 +127,329 @@ fn parse(input: &str) -> Vec<Machine> {
     machines
 }
 
-fn part2(input: &str) -> i32 {
-    let machines = parse(input);
-    let mut total = 0;
+#[derive(Debug, PartialEq, Eq)]
+enum MachineError {
+    /// A button wired to a counter the machine does not have
+    UnknownCounter { line: usize, counter: usize },
+    /// No combination of presses reaches the joltage levels
+    Infeasible { line: usize },
+}
 
-    for machine in &machines {
-        let mut problem = ProblemVariables::new();
+impl std::fmt::Display for MachineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MachineError::UnknownCounter { line, counter } => {
+                write!(
+                    f,
+                    "line {}: a button is wired to missing counter {}",
+                    line, counter
+                )
+            }
+            MachineError::Infeasible { line } => {
+                write!(
+                    f,
+                    "line {}: no button presses reach the joltage levels",
+                    line
+                )
+            }
+        }
+    }
+}
 
-        // One integer variable per button
-        let vars: Vec<Variable> = machine
-            .buttons
+impl std::error::Error for MachineError {}
+
+fn part2(input: &str) -> Result<u64, MachineError> {
+    parse(input)
+        .iter()
+        .enumerate()
+        .map(|(i, machine)| {
+            let line = i + 1;
+            let counters = machine.joltage.len();
+            if let Some(&counter) = machine.buttons.iter().flatten().find(|&&c| c >= counters) {
+                return Err(MachineError::UnknownCounter { line, counter });
+            }
+            fewest_presses(machine).ok_or(MachineError::Infeasible { line })
+        })
+        .sum()
+}
+
+/// The fewest presses that bring every counter to its joltage level, or
+/// None if no combination does.
+///
+/// Gaussian elimination over the rationals leaves each pivot button's
+/// presses as an affine function of the remaining free buttons, so only the
+/// free buttons are searched. Each row is kept as integers by scaling it by
+/// its common denominator.
+fn fewest_presses(machine: &Machine) -> Option<u64> {
+    let joltage: Vec<i128> = machine.joltage.iter().map(|&j| j as i128).collect();
+    // A button that adds to nothing is never worth pressing, and one wired
+    // like another can leave its presses to the other
+    let mut buttons: Vec<Vec<usize>> = machine
+        .buttons
+        .iter()
+        .map(|b| {
+            let mut b: Vec<usize> = b.iter().copied().filter(|&i| i < joltage.len()).collect();
+            b.sort_unstable();
+            b.dedup();
+            b
+        })
+        .filter(|b| !b.is_empty())
+        .collect();
+    buttons.sort_unstable();
+    buttons.dedup();
+
+    let n = buttons.len();
+    // One row per counter: which buttons add to it, then its joltage
+    let mut rows: Vec<Vec<i128>> = joltage
+        .iter()
+        .enumerate()
+        .map(|(i, &jolt)| {
+            let mut row: Vec<i128> = buttons.iter().map(|b| b.contains(&i) as i128).collect();
+            row.push(jolt);
+            row
+        })
+        .collect();
+
+    let mut pivots = Vec::new();
+    for c in 0..n {
+        let r = pivots.len();
+        let Some(k) = (r..rows.len()).find(|&k| rows[k][c] != 0) else {
+            continue;
+        };
+        rows.swap(r, k);
+        if rows[r][c] < 0 {
+            rows[r].iter_mut().for_each(|x| *x = -*x);
+        }
+        reduce(&mut rows[r]);
+        let pivot_row = rows[r].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let b = row[c];
+            if i != r && b != 0 {
+                // The pivot is positive, so other pivots keep their sign
+                for (x, &y) in row.iter_mut().zip(&pivot_row) {
+                    *x = *x * pivot_row[c] - y * b;
+                }
+                reduce(row);
+            }
+        }
+        pivots.push(c);
+    }
+    // Every other row now reads 0 = joltage
+    if rows[pivots.len()..].iter().any(|row| row[n] != 0) {
+        return None;
+    }
+    rows.truncate(pivots.len());
+
+    let free: Vec<usize> = (0..n).filter(|c| !pivots.contains(c)).collect();
+    FreeButtons::new(&rows, &pivots, &free, &buttons, joltage).fewest_presses()
+}
+
+/// Divide a row by the greatest common divisor of its entries.
+fn reduce(row: &mut [i128]) {
+    let g = row.iter().fold(0, |g, &x| gcd(g, x.abs()));
+    if g > 1 {
+        row.iter_mut().for_each(|x| *x /= g);
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// A branch and bound search over the free buttons of a reduced machine.
+///
+/// Pivot row `p` reads `pivot[p] * x + sum(row[p][t] * free t) = rhs[p]`.
+/// Total presses, multiplied by `scale` so they stay integers, are `base`
+/// plus `weight[t]` for each press of free button `t`.
+struct FreeButtons {
+    pivot: Vec<i128>,
+    rhs: Vec<i128>,
+    row: Vec<Vec<i128>>,
+    weight: Vec<i128>,
+    scale: i128,
+    base: i128,
+    /// The counters each free button adds to
+    covers: Vec<Vec<usize>>,
+    joltage: Vec<i128>,
+    /// The most the free buttons from `t` onwards can add to each row's rhs
+    headroom: Vec<Vec<i128>>,
+    /// The most the free buttons from `t` onwards can take off the total
+    discount: Vec<i128>,
+}
+
+impl FreeButtons {
+    fn new(
+        rows: &[Vec<i128>],
+        pivots: &[usize],
+        free: &[usize],
+        buttons: &[Vec<usize>],
+        joltage: Vec<i128>,
+    ) -> Self {
+        let n = buttons.len();
+        let pivot: Vec<i128> = rows.iter().zip(pivots).map(|(row, &c)| row[c]).collect();
+        let rhs: Vec<i128> = rows.iter().map(|row| row[n]).collect();
+        let row: Vec<Vec<i128>> = rows
+            .iter()
+            .map(|row| free.iter().map(|&f| row[f]).collect())
+            .collect();
+        let covers: Vec<Vec<usize>> = free.iter().map(|&f| buttons[f].clone()).collect();
+        // No button can be pressed more often than its lowest counter's level
+        let limit: Vec<i128> = covers
             .iter()
-            .map(|_| problem.add(variable().integer().min(0)))
+            .map(|c| c.iter().map(|&i| joltage[i]).min().unwrap_or(0))
             .collect();
 
-        // Minimize total button presses
-        let objective: Expression = vars.iter().copied().sum();
-        let mut model = problem.minimise(objective).using(microlp);
-
-        // For each light index, sum the presses of buttons that affect it == joltage[i]
-        for (i, &jolt) in machine.joltage.iter().enumerate() {
-            let expr: Expression = machine
-                .buttons
-                .iter()
-                .enumerate()
-                .filter(|(_, lights)| lights.contains(&i))
-                .map(|(b, _)| vars[b])
-                .sum();
-            model = model.with(constraint!(expr == jolt as f64));
+        let scale = pivot.iter().fold(1, |l, &d| l / gcd(l, d) * d);
+        let base = (0..pivot.len()).map(|p| scale / pivot[p] * rhs[p]).sum();
+        let weight: Vec<i128> = (0..free.len())
+            .map(|t| {
+                scale
+                    - (0..pivot.len())
+                        .map(|p| scale / pivot[p] * row[p][t])
+                        .sum::<i128>()
+            })
+            .collect();
+
+        let mut headroom = vec![vec![0; pivot.len()]; free.len() + 1];
+        let mut discount = vec![0; free.len() + 1];
+        for t in (0..free.len()).rev() {
+            for p in 0..pivot.len() {
+                headroom[t][p] = headroom[t + 1][p] + (-row[p][t] * limit[t]).max(0);
+            }
+            discount[t] = discount[t + 1] + (weight[t] * limit[t]).min(0);
+        }
+
+        FreeButtons {
+            pivot,
+            rhs,
+            row,
+            weight,
+            scale,
+            base,
+            covers,
+            joltage,
+            headroom,
+            discount,
+        }
+    }
+
+    fn fewest_presses(&self) -> Option<u64> {
+        let mut best = None;
+        let mut rhs = self.rhs.clone();
+        let mut counters = self.joltage.clone();
+        self.search(0, &mut rhs, &mut counters, 0, self.base, &mut best);
+        best.map(|total| (total / self.scale) as u64)
+    }
+
+    /// Try every number of presses of the `t`th free button onwards, given
+    /// what is left of each row's rhs and each counter's level, the free
+    /// presses so far and the scaled total they lead to.
+    fn search(
+        &self,
+        t: usize,
+        rhs: &mut [i128],
+        counters: &mut [i128],
+        pressed: i128,
+        total: i128,
+        best: &mut Option<i128>,
+    ) {
+        // A press adds at most one to each counter, so the counter furthest
+        // from its level needs at least that many more
+        let needed = pressed + counters.iter().max().copied().unwrap_or(0);
+        if best.is_some_and(|b| needed * self.scale >= b || total + self.discount[t] >= b) {
+            return;
+        }
+        if t == self.covers.len() {
+            if (0..rhs.len()).all(|p| rhs[p] >= 0 && rhs[p] % self.pivot[p] == 0) {
+                *best = Some(total);
+            }
+            return;
+        }
+
+        // Only press counts that overfill no counter and leave every pivot
+        // button a chance of a non-negative count after the later buttons
+        let covers = &self.covers[t];
+        let mut lo = 0;
+        let mut hi = covers.iter().map(|&i| counters[i]).min().unwrap_or(0);
+        for (p, &r) in rhs.iter().enumerate() {
+            let a = self.row[p][t];
+            let room = r + self.headroom[t + 1][p];
+            if a > 0 {
+                hi = hi.min(room.div_euclid(a));
+            } else if a < 0 {
+                lo = lo.max((-room).div_euclid(-a) + ((-room).rem_euclid(-a) != 0) as i128);
+            }
+        }
+        if lo > hi {
+            return;
         }
 
-        if let Ok(solution) = model.solve() {
-            total += vars.iter().map(|v| solution.value(*v) as i32).sum::<i32>();
+        // Cheapest first, so a good answer turns up early to prune against
+        let w = self.weight[t];
+        let order: Box<dyn Iterator<Item = i128>> = if w < 0 {
+            Box::new((lo..=hi).rev())
+        } else {
+            Box::new(lo..=hi)
+        };
+        for presses in order {
+            if best.is_some_and(|b| total + presses * w + self.discount[t + 1] >= b) {
+                break;
+            }
+            for (p, r) in rhs.iter_mut().enumerate() {
+                *r -= self.row[p][t] * presses;
+            }
+            covers.iter().for_each(|&i| counters[i] -= presses);
+            self.search(
+                t + 1,
+                rhs,
+                counters,
+                pressed + presses,
+                total + presses * w,
+                best,
+            );
+            for (p, r) in rhs.iter_mut().enumerate() {
+                *r += self.row[p][t] * presses;
+            }
+            covers.iter().for_each(|&i| counters[i] += presses);
         }
     }
+}
+
+/// The same minimum found by an LP solver, rounded to the nearest integer.
+fn lp_presses(machine: &Machine) -> Option<u64> {
+    let mut problem = ProblemVariables::new();
+
+    // One integer variable per button
+    let vars: Vec<Variable> = machine
+        .buttons
+        .iter()
+        .map(|_| problem.add(variable().integer().min(0)))
+        .collect();
+
+    // Minimize total button presses
+    let objective: Expression = vars.iter().copied().sum();
+    let mut model = problem.minimise(objective).using(microlp);
+
+    // For each light index, sum the presses of buttons that affect it == joltage[i]
+    for (i, &jolt) in machine.joltage.iter().enumerate() {
+        let expr: Expression = machine
+            .buttons
+            .iter()
+            .enumerate()
+            .filter(|(_, lights)| lights.contains(&i))
+            .map(|(b, _)| vars[b])
+            .sum();
+        model = model.with(constraint!(expr == jolt as f64));
+    }
 
-    total
+    let solution = model.solve().ok()?;
+    Some(vars.iter().map(|v| solution.value(*v).round() as u64).sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::xorshift;
 
     const EXAMPLE: &str = "\
 [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
@@ -160,6 +463,64 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(EXAMPLE), 33);
+        assert_eq!(part2(EXAMPLE), Ok(33));
+    }
+
+    #[test]
+    fn test_invalid_machines() {
+        assert_eq!(
+            part2("[.#] (0) (0,1) {5,3}\n[#.] (0) (2) {1,1}"),
+            Err(MachineError::UnknownCounter {
+                line: 2,
+                counter: 2
+            })
+        );
+        // Counter 1 can only ever be one higher than counter 0
+        assert_eq!(
+            part2("[.#] (0) (0,1) {3,5}"),
+            Err(MachineError::Infeasible { line: 1 })
+        );
+        // Consistent over the rationals, but each button must be pressed half a time
+        assert_eq!(
+            part2("[...] (0,1) (1,2) (0,2) {1,1,1}"),
+            Err(MachineError::Infeasible { line: 1 })
+        );
+    }
+
+    #[test]
+    fn test_matches_lp() {
+        let mut state = 0x2025_0010;
+        for _ in 0..200 {
+            let counters = 1 + (xorshift(&mut state) % 6) as usize;
+            let buttons: Vec<Vec<usize>> = (0..1 + xorshift(&mut state) % 8)
+                .map(|_| {
+                    (0..counters)
+                        .filter(|_| xorshift(&mut state).is_multiple_of(2))
+                        .collect()
+                })
+                .collect();
+            // Half the machines are built from presses that reach their levels
+            let joltage = if xorshift(&mut state).is_multiple_of(2) {
+                let mut joltage = vec![0; counters];
+                for b in &buttons {
+                    let presses = (xorshift(&mut state) % 20) as usize;
+                    b.iter().for_each(|&i| joltage[i] += presses);
+                }
+                joltage
+            } else {
+                (0..counters)
+                    .map(|_| (xorshift(&mut state) % 30) as usize)
+                    .collect()
+            };
+            let machine = Machine {
+                diagram: LightDiagram {
+                    value: 0,
+                    length: counters,
+                },
+                buttons,
+                joltage,
+            };
+            assert_eq!(fewest_presses(&machine), lp_presses(&machine));
+        }
     }
 }
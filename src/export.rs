@@ -0,0 +1,299 @@
+use crate::visualize::Frame;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::Duration;
+
+pub type Rgb = [u8; 3];
+
+/// The colour each kind of cell is drawn in.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::export::Palette;
+/// let palette = Palette::new([0, 0, 0]).with('@', [255, 200, 0]);
+/// assert_eq!(palette.colour('@'), [255, 200, 0]);
+/// assert_eq!(palette.colour('?'), [0, 0, 0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colours: HashMap<char, Rgb>,
+    fallback: Rgb,
+}
+
+impl Palette {
+    /// A palette that draws every cell in `fallback`.
+    pub fn new(fallback: Rgb) -> Self {
+        Palette {
+            colours: HashMap::new(),
+            fallback,
+        }
+    }
+
+    /// Draw `c` in `colour`.
+    pub fn with(mut self, c: char, colour: Rgb) -> Self {
+        self.colours.insert(c, colour);
+        self
+    }
+
+    pub fn colour(&self, c: char) -> Rgb {
+        self.colours.get(&c).copied().unwrap_or(self.fallback)
+    }
+}
+
+impl Default for Palette {
+    /// Colours for the cells used by the days' frames, on a dark background.
+    fn default() -> Self {
+        Palette::new([140, 140, 150])
+            .with('.', [24, 24, 32])
+            .with('@', [230, 190, 60])
+            .with('x', [220, 60, 60])
+            .with('S', [80, 200, 80])
+            .with('|', [80, 200, 230])
+            .with('^', [200, 80, 200])
+            .with('#', [230, 40, 40])
+            .with('X', [60, 170, 80])
+            .with('O', [250, 230, 100])
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    /// The image would be larger than the format allows
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    /// A GIF can only use 256 colours
+    TooManyColours(usize),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "{}", e),
+            ExportError::Png(e) => write!(f, "PNG encoding failed: {}", e),
+            ExportError::Gif(e) => write!(f, "GIF encoding failed: {}", e),
+            ExportError::TooLarge { width, height } => {
+                write!(f, "{}x{} pixels is too large", width, height)
+            }
+            ExportError::TooManyColours(n) => {
+                write!(f, "{} colours do not fit in a GIF palette", n)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(e: png::EncodingError) -> Self {
+        ExportError::Png(e)
+    }
+}
+
+impl From<gif::EncodingError> for ExportError {
+    fn from(e: gif::EncodingError) -> Self {
+        ExportError::Gif(e)
+    }
+}
+
+/// Draws frames as images, each cell a `cell_size` pixel square.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    palette: Palette,
+    cell_size: usize,
+}
+
+impl Renderer {
+    pub fn new(palette: Palette, cell_size: usize) -> Self {
+        assert!(cell_size > 0, "cells must be at least one pixel");
+        Renderer { palette, cell_size }
+    }
+
+    /// Image size in pixels.
+    pub fn size(&self, frame: &Frame) -> (usize, usize) {
+        (
+            frame.width() * self.cell_size,
+            frame.height() * self.cell_size,
+        )
+    }
+
+    /// The colour of every pixel, row by row.
+    pub fn pixels(&self, frame: &Frame) -> Vec<Rgb> {
+        let (width, height) = self.size(frame);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in frame.rows() {
+            let line: Vec<Rgb> = row
+                .iter()
+                .flat_map(|&c| std::iter::repeat_n(self.palette.colour(c), self.cell_size))
+                .collect();
+            for _ in 0..self.cell_size {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        pixels
+    }
+
+    pub fn write_png(&self, frame: &Frame, path: &Path) -> Result<(), ExportError> {
+        let (width, height) = self.size(frame);
+        let too_large = ExportError::TooLarge { width, height };
+        let (Ok(w), Ok(h)) = (u32::try_from(width), u32::try_from(height)) else {
+            return Err(too_large);
+        };
+
+        let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), w, h);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels(frame).concat())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Write each frame to `frame_0000.png`, `frame_0001.png`, ... in `dir`.
+    pub fn write_pngs(&self, frames: &[Frame], dir: &Path) -> Result<(), ExportError> {
+        std::fs::create_dir_all(dir)?;
+        for (i, frame) in frames.iter().enumerate() {
+            self.write_png(frame, &dir.join(format!("frame_{:04}.png", i)))?;
+        }
+        Ok(())
+    }
+
+    /// Write all frames as one looping animation, showing each for `delay`.
+    pub fn write_gif(
+        &self,
+        frames: &[Frame],
+        delay: Duration,
+        path: &Path,
+    ) -> Result<(), ExportError> {
+        let (width, height) = frames
+            .iter()
+            .map(|f| self.size(f))
+            .fold((1, 1), |(w, h), (fw, fh)| (w.max(fw), h.max(fh)));
+        let too_large = ExportError::TooLarge { width, height };
+        let (Ok(w), Ok(h)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(too_large);
+        };
+
+        // One global palette shared by every frame
+        let mut index: HashMap<Rgb, u8> = HashMap::new();
+        let mut colours: Vec<Rgb> = Vec::new();
+        let mut images = Vec::with_capacity(frames.len());
+        for frame in frames {
+            let mut image = Vec::new();
+            for pixel in self.pixels(frame) {
+                let i = match index.get(&pixel) {
+                    Some(&i) => i,
+                    None => {
+                        let i = u8::try_from(colours.len())
+                            .map_err(|_| ExportError::TooManyColours(colours.len() + 1))?;
+                        index.insert(pixel, i);
+                        colours.push(pixel);
+                        i
+                    }
+                };
+                image.push(i);
+            }
+            images.push((self.size(frame), image));
+        }
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, w, h, &colours.concat())?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        let centiseconds = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+        for ((fw, fh), image) in images {
+            encoder.write_frame(&gif::Frame {
+                width: fw as u16,
+                height: fh as u16,
+                buffer: Cow::Owned(image),
+                delay: centiseconds,
+                ..gif::Frame::default()
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grid;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_pixels() {
+        let frame = Frame::new(Grid::parse("@."), "");
+        let palette = Palette::new([0, 0, 0]).with('@', [255, 0, 0]);
+        let renderer = Renderer::new(palette, 2);
+        assert_eq!(renderer.size(&frame), (4, 2));
+        let red = [255, 0, 0];
+        let black = [0, 0, 0];
+        assert_eq!(
+            renderer.pixels(&frame),
+            [red, red, black, black, red, red, black, black]
+        );
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let dir = temp_dir("png");
+        let frames = [Frame::new(Grid::parse("@x\n.^"), "")];
+        let renderer = Renderer::new(Palette::default(), 3);
+        renderer.write_pngs(&frames, &dir).unwrap();
+
+        let file = File::open(dir.join("frame_0000.png")).unwrap();
+        let mut reader = png::Decoder::new(file).read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (6, 6));
+        assert_eq!(buf[..3], Palette::default().colour('@'));
+        assert_eq!(
+            buf[info.line_size * 5 + 15..][..3],
+            Palette::default().colour('^')
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_gif_frames() {
+        let dir = temp_dir("gif");
+        let path = dir.join("rolls.gif");
+        let frames = [
+            Frame::new(Grid::parse("@@\n@@"), ""),
+            Frame::new(Grid::parse("x@\n@x"), ""),
+            Frame::new(Grid::parse(".x\nx."), ""),
+        ];
+        let renderer = Renderer::new(Palette::default(), 1);
+        renderer
+            .write_gif(&frames, Duration::from_millis(250), &path)
+            .unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((frame.width, frame.height, frame.delay), (2, 2, 25));
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
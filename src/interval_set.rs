@@ -0,0 +1,303 @@
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+/// Integer types that can be stored in an [`IntervalSet`].
+///
+/// Intervals are closed, so the set needs to step to the neighbouring value
+/// when merging adjacent ranges or building a complement.
+pub trait Discrete: Copy + Ord {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+
+    /// Number of values in `lo..=hi`. Assumes `lo <= hi`.
+    fn span(lo: Self, hi: Self) -> u128;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(
+            impl Discrete for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn span(lo: Self, hi: Self) -> u128 {
+                    (hi as i128 - lo as i128) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_discrete!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A set of integers stored as sorted, disjoint, non-adjacent closed ranges.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::interval_set::IntervalSet;
+/// let fresh: IntervalSet<u64> = [3..=5, 10..=14, 16..=20, 12..=18].into_iter().collect();
+/// assert_eq!(fresh.len(), 14);
+/// assert!(fresh.contains(&17));
+/// assert!(!fresh.contains(&8));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+#[derive(Debug)]
+enum RangeElem<T> {
+    Open(T),
+    Close(T),
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    /// Merge arbitrary, possibly overlapping ranges into a set.
+    ///
+    /// Sweeps over the sorted range endpoints, opening an interval when the
+    /// first range starts and closing it when the last open one ends.
+    pub fn from_ranges<I>(ranges: I) -> Self
+    where
+        I: IntoIterator<Item = RangeInclusive<T>>,
+    {
+        let mut elems = Vec::new();
+        for range in ranges {
+            let (start, end) = range.into_inner();
+            if start <= end {
+                elems.push(RangeElem::Open(start));
+                elems.push(RangeElem::Close(end));
+            }
+        }
+
+        elems.sort_by(|a, b| {
+            let av = match a {
+                RangeElem::Open(v) | RangeElem::Close(v) => v,
+            };
+            let bv = match b {
+                RangeElem::Open(v) | RangeElem::Close(v) => v,
+            };
+
+            av.cmp(bv).then_with(|| match (a, b) {
+                (RangeElem::Open(_), RangeElem::Close(_)) => Ordering::Less,
+                (RangeElem::Close(_), RangeElem::Open(_)) => Ordering::Greater,
+                _ => Ordering::Equal,
+            })
+        });
+
+        let mut opens = 0;
+        let mut start = T::MIN;
+        let mut merged: Vec<(T, T)> = Vec::new();
+        for elem in elems {
+            match elem {
+                RangeElem::Open(v) => {
+                    if 0 == opens {
+                        start = v;
+                    };
+                    opens += 1;
+                }
+                RangeElem::Close(v) => {
+                    if 1 == opens {
+                        // Coalesce with the previous interval if they touch
+                        match merged.last_mut() {
+                            Some(last) if last.1.succ() == Some(start) => last.1 = v,
+                            _ => merged.push((start, v)),
+                        }
+                    };
+                    opens -= 1;
+                }
+            }
+        }
+
+        IntervalSet { ranges: merged }
+    }
+
+    /// Iterate over the disjoint ranges in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+
+    /// Number of disjoint ranges in the set.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Total number of values covered by the set.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(s, e)| T::span(s, e)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Membership test by binary search over the range starts.
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.ranges.partition_point(|&(s, _)| s <= *value);
+        i > 0 && *value <= self.ranges[i - 1].1
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        *self = Self::from_ranges(self.iter().chain(std::iter::once(range)));
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        *self = self.difference(&Self::from_ranges([range]));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.iter().chain(other.iter()))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(T::MIN..=T::MAX))
+    }
+
+    /// All values in `bounds` that are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let (lo, hi) = bounds.into_inner();
+        let mut ranges = Vec::new();
+        if lo > hi {
+            return IntervalSet { ranges };
+        }
+
+        let mut next = Some(lo);
+        for &(s, e) in &self.ranges {
+            let Some(gap_start) = next else {
+                break;
+            };
+            if e < gap_start {
+                continue;
+            }
+            if s > hi {
+                break;
+            }
+            if s > gap_start {
+                // s > gap_start >= T::MIN, so s has a predecessor
+                ranges.push((gap_start, s.pred().unwrap()));
+            }
+            next = e.succ();
+        }
+
+        if let Some(gap_start) = next
+            && gap_start <= hi
+        {
+            ranges.push((gap_start, hi));
+        }
+
+        IntervalSet { ranges }
+    }
+}
+
+impl<T: Discrete> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        Self::from_ranges(iter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(s, e)| s..=e).collect()
+    }
+
+    fn members(set: &IntervalSet<i32>) -> Vec<i32> {
+        set.iter().flatten().collect()
+    }
+
+    #[test]
+    fn test_from_ranges_merges_overlapping_and_adjacent() {
+        let s = set(&[(10, 14), (3, 5), (16, 20), (12, 18), (6, 6), (30, 29)]);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![3..=6, 10..=20]);
+        assert_eq!(s.len(), 15);
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[(3, 5), (10, 14)]);
+        let inside: Vec<i32> = (0..20).filter(|v| s.contains(v)).collect();
+        assert_eq!(inside, vec![3, 4, 5, 10, 11, 12, 13, 14]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+        assert_eq!(a.complement(-5..=35), set(&[(-5, -1), (11, 19), (31, 35)]));
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut s = IntervalSet::new();
+        s.insert(1..=4);
+        s.insert(8..=9);
+        s.insert(5..=7);
+        assert_eq!(members(&s), (1..=9).collect::<Vec<_>>());
+
+        s.remove(3..=4);
+        assert_eq!(s, set(&[(1, 2), (5, 9)]));
+        s.remove(0..=100);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn test_extreme_bounds() {
+        let s: IntervalSet<u8> = [0..=10, 250..=255].into_iter().collect();
+        assert_eq!(
+            s.complement(0..=255).iter().collect::<Vec<_>>(),
+            vec![11..=249]
+        );
+        assert_eq!(s.complement(0..=255).complement(0..=255), s);
+        assert_eq!(IntervalSet::from_ranges([0u64..=u64::MAX]).len(), 1 << 64);
+    }
+}
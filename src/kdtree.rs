@@ -0,0 +1,342 @@
+use crate::point::Point;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A k-d tree over points in `N` dimensions.
+///
+/// The tree is implicit: each node is the median of its slice of `order`,
+/// with the points below it on that level's axis before it and the points
+/// above after it. Alongside each node is the bounding box of its subtree.
+///
+/// Searches panic if two points' squared distance overflows an `i128`, as in
+/// [`Point::distance_squared`]. The bounding boxes can be wider than any
+/// pair; their bounds saturate instead.
+#[derive(Debug, Clone)]
+pub struct KdTree<const N: usize> {
+    points: Vec<Point<N>>,
+    order: Vec<usize>,
+    bounds: Vec<([i64; N], [i64; N])>,
+}
+
+impl<const N: usize> KdTree<N> {
+    pub fn new(points: Vec<Point<N>>) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        let mut bounds = vec![([0; N], [0; N]); points.len()];
+        build(&points, &mut order, &mut bounds, 0);
+        KdTree {
+            points,
+            order,
+            bounds,
+        }
+    }
+
+    pub fn points(&self) -> &[Point<N>] {
+        &self.points
+    }
+
+    /// The point closest to point `i`, other than itself, ordering points by
+    /// (squared distance, index) and skipping those up to and including `after`.
+    /// Calling this with the previous answer walks outwards from `i`.
+    pub fn next_neighbour(&self, i: usize, after: Option<(i128, usize)>) -> Option<(i128, usize)> {
+        let mut best = None;
+        self.search(0, self.order.len(), 0, i, after, &mut best);
+        best
+    }
+
+    fn search(
+        &self,
+        start: usize,
+        end: usize,
+        axis: usize,
+        i: usize,
+        after: Option<(i128, usize)>,
+        best: &mut Option<(i128, usize)>,
+    ) {
+        if start == end {
+            return;
+        }
+        let mid = start + (end - start) / 2;
+        let target = &self.points[i];
+        let (nearest, furthest) = self.box_distances(mid, target);
+        if best.is_some_and(|(d, _)| nearest > d) || after.is_some_and(|(d, _)| furthest < d) {
+            return;
+        }
+
+        let j = self.order[mid];
+        if j != i {
+            let key = (target.distance_squared(&self.points[j]), j);
+            if after.is_none_or(|a| key > a) && best.is_none_or(|b| key < b) {
+                *best = Some(key);
+            }
+        }
+        let next = (axis + 1) % N;
+        if target[axis] < self.points[j][axis] {
+            self.search(start, mid, next, i, after, best);
+            self.search(mid + 1, end, next, i, after, best);
+        } else {
+            self.search(mid + 1, end, next, i, after, best);
+            self.search(start, mid, next, i, after, best);
+        }
+    }
+
+    /// Squared distances from `target` to the nearest and furthest corners of
+    /// the bounding box at `mid`.
+    fn box_distances(&self, mid: usize, target: &Point<N>) -> (i128, i128) {
+        let (lo, hi) = &self.bounds[mid];
+        let (mut nearest, mut furthest) = (0i128, 0i128);
+        for k in 0..N {
+            let t = target[k] as i128;
+            let (lo, hi) = (lo[k] as i128, hi[k] as i128);
+            let gap = (lo - t).max(t - hi).max(0);
+            let reach = (t - lo).max(hi - t);
+            // Saturating only ever loosens the bounds, so nothing is wrongly pruned
+            nearest = gap.saturating_mul(gap).saturating_add(nearest);
+            furthest = reach.saturating_mul(reach).saturating_add(furthest);
+        }
+        (nearest, furthest)
+    }
+
+    /// For every point `i`, the closest point outside its group `group[i]`,
+    /// ordered as in [`KdTree::next_neighbour`]. Subtrees lying wholly in
+    /// `i`'s group are skipped without being searched.
+    pub fn nearest_in_other_group(&self, group: &[usize]) -> Vec<Option<(i128, usize)>> {
+        let mut groups = Groups {
+            of: group,
+            shared: vec![None; self.order.len()],
+        };
+        if !self.order.is_empty() {
+            self.label(0, self.order.len(), &mut groups);
+        }
+        (0..self.points.len())
+            .map(|i| {
+                let mut best = None;
+                self.search_other_group(0, self.order.len(), 0, i, &groups, &mut best);
+                best
+            })
+            .collect()
+    }
+
+    fn label(&self, start: usize, end: usize, groups: &mut Groups) -> Option<usize> {
+        let mid = start + (end - start) / 2;
+        let g = groups.of[self.order[mid]];
+        let mut same = Some(g);
+        for (s, e) in [(start, mid), (mid + 1, end)] {
+            if s < e && self.label(s, e, groups) != Some(g) {
+                same = None;
+            }
+        }
+        groups.shared[mid] = same;
+        same
+    }
+
+    fn search_other_group(
+        &self,
+        start: usize,
+        end: usize,
+        axis: usize,
+        i: usize,
+        groups: &Groups,
+        best: &mut Option<(i128, usize)>,
+    ) {
+        if start == end {
+            return;
+        }
+        let mid = start + (end - start) / 2;
+        if groups.shared[mid] == Some(groups.of[i]) {
+            return;
+        }
+        let target = &self.points[i];
+        let (nearest, _) = self.box_distances(mid, target);
+        if best.is_some_and(|(d, _)| nearest > d) {
+            return;
+        }
+
+        let j = self.order[mid];
+        if groups.of[j] != groups.of[i] {
+            let key = (target.distance_squared(&self.points[j]), j);
+            if best.is_none_or(|b| key < b) {
+                *best = Some(key);
+            }
+        }
+        let next = (axis + 1) % N;
+        let (near, far) = if target[axis] < self.points[j][axis] {
+            ((start, mid), (mid + 1, end))
+        } else {
+            ((mid + 1, end), (start, mid))
+        };
+        self.search_other_group(near.0, near.1, next, i, groups, best);
+        self.search_other_group(far.0, far.1, next, i, groups, best);
+    }
+
+    /// Every pair of points as `(i, j, squared distance)` with `i < j`, in
+    /// increasing order of distance and then of `i` and `j`.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, N> {
+        let heap = (0..self.points.len())
+            .filter_map(|i| {
+                let (d, j) = self.next_neighbour(i, None)?;
+                Some(Reverse((d, i, j)))
+            })
+            .collect();
+        ClosestPairs { tree: self, heap }
+    }
+}
+
+fn build<const N: usize>(
+    points: &[Point<N>],
+    order: &mut [usize],
+    bounds: &mut [([i64; N], [i64; N])],
+    axis: usize,
+) {
+    if order.is_empty() {
+        return;
+    }
+    let mut lo = [i64::MAX; N];
+    let mut hi = [i64::MIN; N];
+    for &i in order.iter() {
+        for k in 0..N {
+            lo[k] = lo[k].min(points[i][k]);
+            hi[k] = hi[k].max(points[i][k]);
+        }
+    }
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by_key(mid, |&i| points[i][axis]);
+    bounds[mid] = (lo, hi);
+    let (lower, upper) = order.split_at_mut(mid);
+    let (lower_bounds, upper_bounds) = bounds.split_at_mut(mid);
+    build(points, lower, lower_bounds, (axis + 1) % N);
+    build(
+        points,
+        &mut upper[1..],
+        &mut upper_bounds[1..],
+        (axis + 1) % N,
+    );
+}
+
+/// Each point's group, and the group shared by every point under each node
+/// if they all have the same one.
+struct Groups<'a> {
+    of: &'a [usize],
+    shared: Vec<Option<usize>>,
+}
+
+/// Lazily merges every point's neighbours, nearest first, holding one
+/// candidate per point.
+pub struct ClosestPairs<'a, const N: usize> {
+    tree: &'a KdTree<N>,
+    heap: BinaryHeap<Reverse<(i128, usize, usize)>>,
+}
+
+impl<const N: usize> Iterator for ClosestPairs<'_, N> {
+    type Item = (usize, usize, i128);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Reverse((d, i, j)) = self.heap.pop()?;
+            if let Some((next_d, k)) = self.tree.next_neighbour(i, Some((d, j))) {
+                self.heap.push(Reverse((next_d, i, k)));
+            }
+            // Each pair comes up once from each end; keep the one from the lower index
+            if i < j {
+                return Some((i, j, d));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xorshift;
+
+    #[test]
+    fn test_next_neighbour() {
+        let tree = KdTree::new(
+            [[0, 0], [3, 4], [1, 1], [-1, -1], [10, 0]]
+                .map(Point)
+                .to_vec(),
+        );
+        assert_eq!(tree.next_neighbour(0, None), Some((2, 2)));
+        // Ties go to the lower index
+        assert_eq!(tree.next_neighbour(0, Some((2, 2))), Some((2, 3)));
+        assert_eq!(tree.next_neighbour(0, Some((2, 3))), Some((25, 1)));
+        assert_eq!(tree.next_neighbour(0, Some((100, 4))), None);
+    }
+
+    #[test]
+    fn test_closest_pairs_match_sorted_list() {
+        let mut state = 0x2025_0008;
+        for _ in 0..100 {
+            let n = (xorshift(&mut state) % 40) as usize;
+            // A small range forces repeated points and tied distances
+            let range = 1 + xorshift(&mut state) % 20;
+            let points: Vec<Point<3>> = (0..n)
+                .map(|_| Point([(); 3].map(|_| (xorshift(&mut state) % range) as i64 - 5)))
+                .collect();
+
+            let mut expected = Vec::new();
+            for i in 0..n {
+                for j in i + 1..n {
+                    expected.push((i, j, points[i].distance_squared(&points[j])));
+                }
+            }
+            expected.sort_unstable_by_key(|&(i, j, d)| (d, i, j));
+
+            let tree = KdTree::new(points);
+            assert_eq!(tree.closest_pairs().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_nearest_in_other_group() {
+        let mut state = 0x2025_0847;
+        for _ in 0..50 {
+            let n = (xorshift(&mut state) % 40) as usize;
+            let groups = 1 + xorshift(&mut state) % 5;
+            let points: Vec<Point<2>> = (0..n)
+                .map(|_| Point([(); 2].map(|_| (xorshift(&mut state) % 10) as i64)))
+                .collect();
+            let group: Vec<usize> = (0..n)
+                .map(|_| (xorshift(&mut state) % groups) as usize)
+                .collect();
+
+            let expected: Vec<Option<(i128, usize)>> = (0..n)
+                .map(|i| {
+                    (0..n)
+                        .filter(|&j| group[j] != group[i])
+                        .map(|j| (points[i].distance_squared(&points[j]), j))
+                        .min()
+                })
+                .collect();
+            let tree = KdTree::new(points);
+            assert_eq!(tree.nearest_in_other_group(&group), expected);
+        }
+    }
+
+    #[test]
+    fn test_far_coordinates() {
+        let (min, max) = (i32::MIN as i64, i32::MAX as i64);
+        let tree = KdTree::new(
+            [[min, min, min], [max, max, max], [0, 0, 0]]
+                .map(Point)
+                .to_vec(),
+        );
+        let pairs: Vec<_> = tree.closest_pairs().collect();
+        assert_eq!(pairs[0], (1, 2, 3 * (max as i128).pow(2)));
+        assert_eq!(pairs[1], (0, 2, 3 * (min as i128).pow(2)));
+        assert_eq!(pairs[2], (0, 1, 3 * (u32::MAX as i128).pow(2)));
+
+        // Every pair fits in an i128, but the whole box is too wide to
+        let m = 8_000_000_000_000_000_000;
+        let points = [[m, 0, 0], [0, m, 0], [0, 0, m], [0, 0, 0]].map(Point);
+        let mut expected = Vec::new();
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let d = points[i].checked_distance_squared(&points[j]).unwrap();
+                expected.push((i, j, d));
+            }
+        }
+        expected.sort_unstable_by_key(|&(i, j, d)| (d, i, j));
+        let tree = KdTree::new(points.to_vec());
+        assert_eq!(tree.closest_pairs().collect::<Vec<_>>(), expected);
+    }
+}
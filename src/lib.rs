@@ -1,4 +1,13 @@
+pub mod answer;
 pub mod days;
+pub mod export;
+pub mod interval_set;
+pub mod kdtree;
+pub mod mst;
+pub mod point;
+pub mod polygon;
+pub mod subsequence;
+pub mod visualize;
 
 use std::fs;
 use std::path::Path;
@@ -83,6 +92,63 @@ pub fn split_by_blank_lines(input: &str) -> Vec<String> {
     input.split("\n\n").map(|s| s.to_string()).collect()
 }
 
+/// Deterministic xorshift generator for randomised tests, so they need no
+/// extra dependencies and failures are reproducible
+#[cfg(test)]
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Extra command-line options given after the day number, e.g.
+/// `cargo run 2 --base 16 --min-repeats 3`
+#[derive(Debug, Default)]
+pub struct Options {
+    args: Vec<String>,
+}
+
+impl Options {
+    pub fn from_args(args: &[String]) -> Self {
+        Options {
+            args: args.to_vec(),
+        }
+    }
+
+    /// Whether a flag such as `--visualize` was given
+    pub fn flag(&self, name: &str) -> bool {
+        self.args.iter().any(|a| a == name)
+    }
+
+    /// Parse the value following an option such as `--base 16`
+    ///
+    /// # Example
+    /// ```
+    /// # use advent_of_code_2025::Options;
+    /// let args = vec!["--base".to_string(), "16".to_string()];
+    /// let options = Options::from_args(&args);
+    /// assert_eq!(options.value::<u32>("--base"), Some(16));
+    /// assert_eq!(options.value::<u32>("--min-repeats"), None);
+    /// ```
+    pub fn value<T>(&self, name: &str) -> Option<T>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Debug,
+    {
+        let i = self.args.iter().position(|a| a == name)?;
+        let value = self
+            .args
+            .get(i + 1)
+            .unwrap_or_else(|| panic!("Missing value for {}", name));
+        Some(
+            value
+                .parse()
+                .unwrap_or_else(|e| panic!("Invalid value {:?} for {}: {:?}", value, name, e)),
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Grid<T> {
     width: usize,
@@ -114,47 +180,6 @@ impl Grid<char> {
         }
     }
 }
-impl Grid<u64> {
-    fn parse_u64s(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
-        let height = lines.len();
-        let width = lines.first().map_or(0, |l| l.split_whitespace().count());
-
-        assert!(width > 0, "grid must not be empty");
-
-        let mut data = Vec::with_capacity(width * height);
-        for line in lines {
-            let len = line.split_whitespace().count();
-            assert_eq!(len, width, "all lines must have the same length");
-            data.extend(line.split_whitespace().map(|s| s.parse::<u64>().unwrap()));
-        }
-
-        Grid {
-            width,
-            height,
-            data,
-        }
-    }
-}
-
-impl<T: Copy> Grid<T> {
-    pub fn transpose(&self) -> Grid<T> {
-        let mut data = Vec::with_capacity(self.width * self.height);
-
-        for x in 0..self.width {
-            for y in 0..self.height {
-                data.push(self.data[self.index(x, y)]);
-            }
-        }
-
-        Grid {
-            width: self.height,
-            height: self.width,
-            data,
-        }
-    }
-}
-
 impl<T> Grid<T> {
     fn index(&self, x: usize, y: usize) -> usize {
         y * self.width + x
@@ -1,4 +1,4 @@
-use advent_of_code_2025::days;
+use advent_of_code_2025::{Options, days};
 use std::env;
 use std::time::Instant;
 
@@ -6,7 +6,7 @@ fn main() {
     let args: Vec<String> = env::args().collect();
 
     if args.len() < 2 {
-        println!("Usage: cargo run <day>");
+        println!("Usage: cargo run <day> [options]");
         println!("Example: cargo run 1");
         return;
     }
@@ -20,23 +20,25 @@ fn main() {
         return;
     }
 
+    let options = Options::from_args(&args[2..]);
+
     println!("Running Day {}", day);
     println!("---");
 
     let start = Instant::now();
 
     match day {
-        1 => days::day01::solve(),
+        1 => days::day01::solve(&options),
         // Add more days here as you implement them
-        2 => days::day02::solve(),
-        3 => days::day03::solve(),
-        4 => days::day04::solve(),
-        5 => days::day05::solve(),
-        6 => days::day06::solve(),
-        7 => days::day07::solve(),
-        8 => days::day08::solve(),
-        9 => days::day09::solve(),
-        10 => days::day10::solve(),
+        2 => days::day02::solve(&options),
+        3 => days::day03::solve(&options),
+        4 => days::day04::solve(&options),
+        5 => days::day05::solve(&options),
+        6 => days::day06::solve(&options),
+        7 => days::day07::solve(&options),
+        8 => days::day08::solve(&options),
+        9 => days::day09::solve(&options),
+        10 => days::day10::solve(&options),
         _ => println!("Day {} not yet implemented", day),
     }
 
@@ -0,0 +1,315 @@
+/// Disjoint sets over `0..n`, merged by size with path halving.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::mst::UnionFind;
+/// let mut sets = UnionFind::new(4);
+/// assert!(sets.union(0, 1));
+/// assert!(sets.union(1, 2));
+/// assert!(!sets.union(0, 2));
+/// assert_eq!(sets.size(2), 3);
+/// assert_eq!(sets.sets(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            sets: n,
+        }
+    }
+
+    /// The representative of the set containing `i`.
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Merge the sets containing `a` and `b`, returning false if they were
+    /// already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.sets -= 1;
+        true
+    }
+
+    /// Number of elements in the set containing `i`.
+    pub fn size(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets.
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+}
+
+/// The edges of a minimum spanning tree as `(from, to, weight)`, in the
+/// order they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanningTree<W> {
+    vertices: usize,
+    edges: Vec<(usize, usize, W)>,
+}
+
+impl<W: Copy> SpanningTree<W> {
+    pub fn edges(&self) -> &[(usize, usize, W)] {
+        &self.edges
+    }
+
+    pub fn last_edge(&self) -> Option<(usize, usize, W)> {
+        self.edges.last().copied()
+    }
+
+    /// The heaviest edge. For Kruskal's algorithm this is the last edge added.
+    pub fn heaviest_edge(&self) -> Option<(usize, usize, W)>
+    where
+        W: Ord,
+    {
+        self.edges.iter().copied().max_by_key(|&(_, _, w)| w)
+    }
+
+    pub fn total_weight(&self) -> W
+    where
+        W: std::iter::Sum,
+    {
+        self.edges.iter().map(|&(_, _, w)| w).sum()
+    }
+
+    /// False if the graph was disconnected, so this is only a spanning forest.
+    pub fn is_connected(&self) -> bool {
+        self.edges.len() + 1 >= self.vertices
+    }
+}
+
+/// Kruskal's algorithm over `n` vertices and any list of edges. Edges of
+/// equal weight are considered in the order given.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::mst::kruskal;
+/// let tree = kruskal(4, vec![(0, 1, 5), (1, 2, 1), (0, 2, 2), (2, 3, 7), (1, 3, 3)]);
+/// assert_eq!(tree.edges(), [(1, 2, 1), (0, 2, 2), (1, 3, 3)]);
+/// assert_eq!(tree.total_weight(), 6);
+/// ```
+pub fn kruskal<W: Copy + Ord>(n: usize, mut edges: Vec<(usize, usize, W)>) -> SpanningTree<W> {
+    edges.sort_by_key(|&(_, _, w)| w);
+    kruskal_sorted(n, edges)
+}
+
+/// Kruskal's algorithm over edges already in increasing order of weight.
+/// Stops pulling edges as soon as the tree is complete, so `edges` can be a
+/// lazy iterator over a much larger graph.
+pub fn kruskal_sorted<W: Copy>(
+    n: usize,
+    edges: impl IntoIterator<Item = (usize, usize, W)>,
+) -> SpanningTree<W> {
+    let mut sets = UnionFind::new(n);
+    let mut tree = Vec::with_capacity(n.saturating_sub(1));
+    let mut edges = edges.into_iter();
+    while sets.sets() > 1
+        && let Some((i, j, w)) = edges.next()
+    {
+        if sets.union(i, j) {
+            tree.push((i, j, w));
+        }
+    }
+    SpanningTree {
+        vertices: n,
+        edges: tree,
+    }
+}
+
+/// Prim's algorithm over the complete graph on `n` vertices, in O(n²) time
+/// and O(n) memory. No matrix is built: `weight(i, j)` is called as each
+/// pair is needed.
+///
+/// With distinct weights this finds the same tree as [`kruskal`], though
+/// the edges are added in a different order.
+pub fn prim<W: Copy + Ord>(n: usize, weight: impl Fn(usize, usize) -> W) -> SpanningTree<W> {
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+    let mut in_tree = vec![false; n];
+    // The lightest edge from the tree to each vertex outside it
+    let mut lightest: Vec<Option<(W, usize)>> = vec![None; n];
+    let mut current = 0;
+    if n > 0 {
+        in_tree[0] = true;
+    }
+    for _ in 1..n {
+        let mut next: Option<(W, usize, usize)> = None;
+        for v in 0..n {
+            if in_tree[v] {
+                continue;
+            }
+            let w = weight(current, v);
+            if lightest[v].is_none_or(|(best, _)| w < best) {
+                lightest[v] = Some((w, current));
+            }
+            let (w, from) = lightest[v].unwrap();
+            if next.is_none_or(|(best, _, _)| w < best) {
+                next = Some((w, from, v));
+            }
+        }
+        let (w, from, to) = next.unwrap();
+        in_tree[to] = true;
+        edges.push((from, to, w));
+        current = to;
+    }
+    SpanningTree { vertices: n, edges }
+}
+
+/// Borůvka's algorithm: each round every component joins along the lightest
+/// edge leaving it, so there are at most log₂ n rounds.
+///
+/// `lightest_out(component)` is given each vertex's component and returns,
+/// for every vertex, the lightest edge from it to another component as
+/// `(to, weight)`. Ties must be broken, for example on the vertices, or the
+/// tree may not be minimal.
+pub fn boruvka<W: Copy + Ord>(
+    n: usize,
+    mut lightest_out: impl FnMut(&[usize]) -> Vec<Option<(usize, W)>>,
+) -> SpanningTree<W> {
+    let mut sets = UnionFind::new(n);
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+    while sets.sets() > 1 {
+        let component: Vec<usize> = (0..n).map(|v| sets.find(v)).collect();
+        let mut cheapest: Vec<Option<(W, usize, usize)>> = vec![None; n];
+        for (from, edge) in lightest_out(&component).into_iter().enumerate() {
+            if let Some((to, w)) = edge {
+                let c = component[from];
+                if cheapest[c].is_none_or(|(best, _, _)| w < best) {
+                    cheapest[c] = Some((w, from, to));
+                }
+            }
+        }
+        let before = edges.len();
+        for (w, from, to) in cheapest.into_iter().flatten() {
+            if sets.union(from, to) {
+                edges.push((from, to, w));
+            }
+        }
+        // No edges left between the components
+        if edges.len() == before {
+            break;
+        }
+    }
+    SpanningTree { vertices: n, edges }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xorshift;
+
+    #[test]
+    fn test_disconnected() {
+        let tree = kruskal(5, vec![(0, 1, 4), (3, 4, 2), (1, 0, 1)]);
+        assert_eq!(tree.edges(), [(1, 0, 1), (3, 4, 2)]);
+        assert!(!tree.is_connected());
+        assert!(kruskal::<u32>(1, vec![]).is_connected());
+        assert!(prim(0, |_, _| 0).is_connected());
+
+        // Only 0-1 and 3-4 are joined
+        let tree = boruvka(5, |component| {
+            (0..5)
+                .map(|v| match v {
+                    0 | 1 if component[0] != component[1] => Some((1 - v, 4)),
+                    3 | 4 if component[3] != component[4] => Some((7 - v, 2)),
+                    _ => None,
+                })
+                .collect()
+        });
+        assert_eq!(tree.total_weight(), 6);
+        assert!(!tree.is_connected());
+    }
+
+    #[test]
+    fn test_stops_when_connected() {
+        let mut pulled = 0;
+        let edges = (0..).map(|i| {
+            pulled += 1;
+            (i, i + 1, i)
+        });
+        let tree = kruskal_sorted(4, edges);
+        assert_eq!(tree.last_edge(), Some((2, 3, 2)));
+        assert_eq!(pulled, 3);
+    }
+
+    #[test]
+    fn test_prim_and_boruvka_match_kruskal() {
+        let mut state = 0x2025_0047;
+        for _ in 0..50 {
+            let n = (xorshift(&mut state) % 30) as usize;
+            // Break ties on the vertices so the minimum spanning tree is unique
+            let weights: Vec<Vec<(u64, usize, usize)>> = (0..n)
+                .map(|_| {
+                    (0..n)
+                        .map(|_| xorshift(&mut state) % 10)
+                        .collect::<Vec<_>>()
+                })
+                .enumerate()
+                .map(|(i, row)| {
+                    row.into_iter()
+                        .enumerate()
+                        .map(|(j, w)| (w, i.min(j), i.max(j)))
+                        .collect()
+                })
+                .collect();
+            let weight = |i: usize, j: usize| weights[i.min(j)][i.max(j)];
+
+            let mut edges = Vec::new();
+            for i in 0..n {
+                for j in i + 1..n {
+                    edges.push((i, j, weight(i, j)));
+                }
+            }
+            let kruskal = kruskal(n, edges);
+            let prim = prim(n, weight);
+            let boruvka = boruvka(n, |component| {
+                (0..n)
+                    .map(|i| {
+                        (0..n)
+                            .filter(|&j| component[j] != component[i])
+                            .map(|j| (j, weight(i, j)))
+                            .min_by_key(|&(_, w)| w)
+                    })
+                    .collect()
+            });
+
+            let normalised = |tree: &SpanningTree<(u64, usize, usize)>| {
+                let mut edges: Vec<_> = tree.edges().iter().map(|&(_, _, w)| w).collect();
+                edges.sort_unstable();
+                edges
+            };
+            assert_eq!(normalised(&prim), normalised(&kruskal));
+            assert_eq!(normalised(&boruvka), normalised(&kruskal));
+            assert_eq!(
+                prim.heaviest_edge().map(|e| e.2),
+                kruskal.last_edge().map(|e| e.2)
+            );
+            let total = |tree: &SpanningTree<(u64, usize, usize)>| {
+                tree.edges().iter().map(|e| e.2.0).sum::<u64>()
+            };
+            assert_eq!(total(&prim), total(&kruskal));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A point with `N` integer coordinates.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::point::Point;
+/// let a: Point<3> = "162,817,812".parse().unwrap();
+/// let b: Point<3> = "425 690 689".parse().unwrap();
+/// assert_eq!(a.distance_squared(&b), 100427);
+/// assert_eq!(a.manhattan(&b), 513);
+/// assert_eq!(a.chebyshev(&b), 263);
+/// assert_eq!(b - a, Point([263, -127, -123]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Point<N> {
+    pub fn coords(&self) -> [i64; N] {
+        self.0
+    }
+
+    /// Squared Euclidean distance.
+    ///
+    /// # Panics
+    /// If the distance does not fit in an `i128`, which takes
+    /// coordinates around 2⁶³ apart. See [`Point::checked_distance_squared`].
+    pub fn distance_squared(&self, other: &Self) -> i128 {
+        self.checked_distance_squared(other)
+            .expect("squared distance overflows i128")
+    }
+
+    /// Squared Euclidean distance, or `None` if it does not fit in an `i128`.
+    pub fn checked_distance_squared(&self, other: &Self) -> Option<i128> {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .try_fold(0i128, |sum, (&a, &b)| {
+                let d = a as i128 - b as i128;
+                sum.checked_add(d.checked_mul(d)?)
+            })
+    }
+
+    pub fn manhattan(&self, other: &Self) -> u128 {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| a.abs_diff(*b) as u128)
+            .sum()
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> u64 {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl Point<2> {
+    pub fn x(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i64 {
+        self.0[1]
+    }
+}
+
+impl Point<3> {
+    pub fn x(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i64 {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> i64 {
+        self.0[2]
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point(std::array::from_fn(|i| self.0[i] - other.0[i]))
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point(self.0.map(|c| -c))
+    }
+}
+
+impl<const N: usize> Mul<i64> for Point<N> {
+    type Output = Self;
+
+    fn mul(self, k: i64) -> Self {
+        Point(self.0.map(|c| c * k))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePointError {
+    WrongDimensions { expected: usize, found: usize },
+    InvalidCoordinate(String),
+}
+
+impl std::fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParsePointError::WrongDimensions { expected, found } => {
+                write!(f, "expected {} coordinates, found {}", expected, found)
+            }
+            ParsePointError::InvalidCoordinate(text) => {
+                write!(f, "'{}' is not an integer coordinate", text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParsePointError {}
+
+impl<const N: usize> FromStr for Point<N> {
+    type Err = ParsePointError;
+
+    /// Coordinates separated by commas, or by whitespace if there are no commas.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = if s.contains(',') {
+            s.split(',').map(str::trim).collect()
+        } else {
+            s.split_whitespace().collect()
+        };
+        if parts.len() != N {
+            return Err(ParsePointError::WrongDimensions {
+                expected: N,
+                found: parts.len(),
+            });
+        }
+        let mut coords = [0; N];
+        for (c, part) in coords.iter_mut().zip(parts) {
+            *c = part
+                .parse()
+                .map_err(|_| ParsePointError::InvalidCoordinate(part.to_string()))?;
+        }
+        Ok(Point(coords))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("7, 1".parse(), Ok(Point([7, 1])));
+        assert_eq!("  -3\t4 5 ".parse(), Ok(Point([-3, 4, 5])));
+        assert_eq!(
+            "1,2".parse::<Point<3>>(),
+            Err(ParsePointError::WrongDimensions {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "1,,2".parse::<Point<3>>(),
+            Err(ParsePointError::InvalidCoordinate(String::new()))
+        );
+        assert_eq!(
+            "11,x".parse::<Point<2>>(),
+            Err(ParsePointError::InvalidCoordinate("x".to_string()))
+        );
+        assert_eq!(
+            "".parse::<Point<1>>(),
+            Err(ParsePointError::WrongDimensions {
+                expected: 1,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point([i32::MIN as i64, 0]);
+        let b = Point([i32::MAX as i64, -5]);
+        assert_eq!(a.distance_squared(&b), (u32::MAX as i128).pow(2) + 25);
+        assert_eq!(a.manhattan(&b), u32::MAX as u128 + 5);
+        assert_eq!(a.chebyshev(&b), u32::MAX as u64);
+        assert_eq!(Point([]).chebyshev(&Point([])), 0);
+
+        // Two axes of 2⁶³ fit; a third, or one axis of 2⁶⁴, does not
+        let max = i64::MAX as i128;
+        let (o2, o3) = (Point([0, 0]), Point([0, 0, 0]));
+        assert_eq!(
+            o2.checked_distance_squared(&Point([i64::MAX; 2])),
+            Some(2 * max * max)
+        );
+        assert_eq!(o3.checked_distance_squared(&Point([i64::MAX; 3])), None);
+        let (min, max) = (Point([i64::MIN, 0]), Point([i64::MAX, 0]));
+        assert_eq!(min.checked_distance_squared(&max), None);
+        assert_eq!(min.manhattan(&max), u64::MAX as u128);
+        assert_eq!(min.chebyshev(&max), u64::MAX);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mut p = Point([1, -2, 3]);
+        p += Point([1, 1, 1]);
+        assert_eq!(p, Point([2, -1, 4]));
+        p -= Point([2, 0, 0]);
+        assert_eq!(-p * 2, Point([0, 2, -8]));
+        p[2] = 7;
+        assert_eq!((p.x(), p.y(), p.z()), (0, -1, 7));
+    }
+}
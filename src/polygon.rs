@@ -0,0 +1,588 @@
+use crate::point::Point;
+
+/// Whether points on the outline count as inside.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    Inclusive,
+    Exclusive,
+}
+
+/// Why a list of vertices is not a simple rectilinear polygon. Indices are
+/// 0-based positions in the vertex list; edge `i` runs from vertex `i` to the
+/// next one, wrapping around to close the polygon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolygonError {
+    TooFewVertices(usize),
+    /// Vertex `index` repeats the vertex before it
+    DuplicateVertex {
+        index: usize,
+    },
+    /// Edge `index` is neither horizontal nor vertical
+    DiagonalEdge {
+        index: usize,
+    },
+    /// Edges `first` and `second` cross, touch or overlap
+    SelfIntersection {
+        first: usize,
+        second: usize,
+    },
+}
+
+impl std::fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PolygonError::TooFewVertices(n) => {
+                write!(f, "a polygon needs at least 4 vertices, got {}", n)
+            }
+            PolygonError::DuplicateVertex { index } => {
+                write!(f, "vertex {} repeats the previous vertex", index)
+            }
+            PolygonError::DiagonalEdge { index } => {
+                write!(f, "edge from vertex {} is not axis-aligned", index)
+            }
+            PolygonError::SelfIntersection { first, second } => {
+                write!(f, "edges from vertices {} and {} intersect", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// A simple polygon whose edges are all horizontal or vertical, closed by an
+/// edge from the last vertex back to the first.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::point::Point;
+/// # use advent_of_code_2025::polygon::{Boundary, RectilinearPolygon};
+/// let corners = [[0, 0], [2, 0], [2, 2], [0, 2]].map(Point).to_vec();
+/// let square = RectilinearPolygon::new(corners).unwrap();
+/// assert_eq!(square.area(), 4);
+/// assert_eq!(square.lattice_points(), 9);
+/// assert!(square.contains(Point([2, 1]), Boundary::Inclusive));
+/// assert!(!square.contains(Point([2, 1]), Boundary::Exclusive));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Point<2>>,
+}
+
+impl RectilinearPolygon {
+    /// Check that `vertices` trace a simple rectilinear polygon.
+    pub fn new(vertices: Vec<Point<2>>) -> Result<Self, PolygonError> {
+        let n = vertices.len();
+        if n < 4 {
+            return Err(PolygonError::TooFewVertices(n));
+        }
+        for i in 0..n {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            if a == b {
+                return Err(PolygonError::DuplicateVertex { index: (i + 1) % n });
+            }
+            if a.x() != b.x() && a.y() != b.y() {
+                return Err(PolygonError::DiagonalEdge { index: i });
+            }
+        }
+
+        let polygon = RectilinearPolygon { vertices };
+        let edges: Vec<(Point<2>, Point<2>)> = polygon.edges().collect();
+        for i in 0..n {
+            for j in i + 1..n {
+                let adjacent = j == i + 1 || (i == 0 && j == n - 1);
+                let crosses = if adjacent {
+                    // Neighbours share a vertex, but must not double back
+                    let (first, second) = if j == i + 1 { (i, j) } else { (j, i) };
+                    doubles_back(edges[first], edges[second])
+                } else {
+                    boxes_overlap(edges[i], edges[j])
+                };
+                if crosses {
+                    return Err(PolygonError::SelfIntersection {
+                        first: i,
+                        second: j,
+                    });
+                }
+            }
+        }
+        Ok(polygon)
+    }
+
+    pub fn vertices(&self) -> &[Point<2>] {
+        &self.vertices
+    }
+
+    /// Each edge as (from, to), including the closing edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Point<2>, Point<2>)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// The smallest and largest corner of the bounding box.
+    pub fn bounds(&self) -> (Point<2>, Point<2>) {
+        let xs = self.vertices.iter().map(Point::<2>::x);
+        let ys = self.vertices.iter().map(Point::<2>::y);
+        (
+            Point([xs.clone().min().unwrap(), ys.clone().min().unwrap()]),
+            Point([xs.max().unwrap(), ys.max().unwrap()]),
+        )
+    }
+
+    /// Enclosed area by the shoelace formula.
+    pub fn area(&self) -> i128 {
+        let twice: i128 = self
+            .edges()
+            .map(|(a, b)| a.x() as i128 * b.y() as i128 - b.x() as i128 * a.y() as i128)
+            .sum();
+        twice.abs() / 2
+    }
+
+    /// Lattice points on the outline.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges().map(|(a, b)| a.manhattan(&b) as i128).sum()
+    }
+
+    /// Lattice points strictly inside, by Pick's theorem: A = I + B/2 - 1.
+    pub fn interior_points(&self) -> i128 {
+        self.area() - self.boundary_points() / 2 + 1
+    }
+
+    /// Lattice points inside or on the outline.
+    pub fn lattice_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    fn on_boundary(&self, p: Point<2>) -> bool {
+        self.edges().any(|e| boxes_overlap(e, (p, p)))
+    }
+
+    /// Whether `p` is inside the polygon, by casting a ray to the right.
+    pub fn contains(&self, p: Point<2>, boundary: Boundary) -> bool {
+        if self.on_boundary(p) {
+            return boundary == Boundary::Inclusive;
+        }
+        // Count each vertex with the edge below it, so the ray never grazes a corner
+        let crossings = self
+            .edges()
+            .filter(|&(a, b)| a.x() == b.x() && a.x() > p.x())
+            .filter(|&(a, b)| a.y().min(b.y()) <= p.y() && p.y() < a.y().max(b.y()))
+            .count();
+        crossings % 2 == 1
+    }
+
+    /// Build a `TileIndex` for asking which rectangles fit inside.
+    pub fn tile_index(&self) -> TileIndex {
+        TileIndex::new(self)
+    }
+}
+
+/// For axis-aligned segments, whether their bounding boxes share a point.
+fn boxes_overlap((a, b): (Point<2>, Point<2>), (c, d): (Point<2>, Point<2>)) -> bool {
+    (0..2).all(|k| a[k].min(b[k]) <= c[k].max(d[k]) && c[k].min(d[k]) <= a[k].max(b[k]))
+}
+
+/// Whether `next`, which starts where `edge` ends, runs straight back along it.
+fn doubles_back(edge: (Point<2>, Point<2>), next: (Point<2>, Point<2>)) -> bool {
+    let direction = |(a, b): (Point<2>, Point<2>)| Point((b - a).coords().map(i64::signum));
+    direction(edge) == -direction(next)
+}
+
+/// A polygon's lattice points compressed to its vertices' coordinates, for
+/// asking whether a rectangle of lattice points lies inside it in O(1).
+///
+/// Along each axis the even cells are the vertices' coordinates and the odd
+/// cells the gaps between them, so every cell is wholly inside or wholly
+/// outside. A prefix-sum table counts the outside cells.
+#[derive(Debug, Clone)]
+pub struct TileIndex {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    /// `outside[y * stride + x]` counts outside cells above and left of (x, y)
+    outside: Vec<u32>,
+    stride: usize,
+}
+
+impl TileIndex {
+    pub fn new(polygon: &RectilinearPolygon) -> Self {
+        let axis = |mut values: Vec<i64>| {
+            values.sort_unstable();
+            values.dedup();
+            values
+        };
+        let xs = axis(polygon.vertices.iter().map(Point::<2>::x).collect());
+        let ys = axis(polygon.vertices.iter().map(Point::<2>::y).collect());
+        let (width, height) = (2 * xs.len() - 1, 2 * ys.len() - 1);
+        let cell = |p: Point<2>| {
+            (
+                2 * xs.binary_search(&p.x()).unwrap(),
+                2 * ys.binary_search(&p.y()).unwrap(),
+            )
+        };
+
+        let mut boundary = vec![false; width * height];
+        // Vertical edges as (column, first row, last row)
+        let mut verticals = Vec::new();
+        for (a, b) in polygon.edges() {
+            let ((x1, y1), (x2, y2)) = (cell(a), cell(b));
+            for y in y1.min(y2)..=y1.max(y2) {
+                for x in x1.min(x2)..=x1.max(x2) {
+                    boundary[y * width + x] = true;
+                }
+            }
+            if x1 == x2 {
+                verticals.push((x1, y1.min(y2), y1.max(y2)));
+            }
+        }
+
+        // A gap between neighbouring coordinates holds no lattice points
+        let empty = |values: &[i64], i: usize| i % 2 == 1 && values[i / 2 + 1] == values[i / 2] + 1;
+
+        let stride = width + 1;
+        let mut outside = vec![0; stride * (height + 1)];
+        let mut crossings = vec![false; width];
+        for y in 0..height {
+            // Cast rays to the right, counting each vertex row with the edge below it
+            crossings.fill(false);
+            for &(x, top, bottom) in &verticals {
+                if top <= y && y < bottom {
+                    crossings[x] = !crossings[x];
+                }
+            }
+
+            let mut inside = false;
+            let mut row_total = 0;
+            for x in 0..width {
+                inside ^= crossings[x];
+                let is_outside =
+                    !boundary[y * width + x] && !inside && !empty(&xs, x) && !empty(&ys, y);
+                row_total += u32::from(is_outside);
+                outside[(y + 1) * stride + x + 1] = outside[y * stride + x + 1] + row_total;
+            }
+        }
+
+        TileIndex {
+            xs,
+            ys,
+            outside,
+            stride,
+        }
+    }
+
+    /// The cell holding coordinate `v`, if it is within the vertices' range.
+    fn index(values: &[i64], v: i64) -> Option<usize> {
+        match values.binary_search(&v) {
+            Ok(i) => Some(2 * i),
+            Err(i) if i > 0 && i < values.len() => Some(2 * i - 1),
+            Err(_) => None,
+        }
+    }
+
+    /// The cell holding lattice point `p`, or `None` if it is outside the
+    /// bounding box. Look up cells once to check many rectangles between them.
+    pub fn cell(&self, p: Point<2>) -> Option<(usize, usize)> {
+        Some((Self::index(&self.xs, p.x())?, Self::index(&self.ys, p.y())?))
+    }
+
+    /// Whether every cell between corner cells `a` and `b` is inside.
+    pub fn contains_cells(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        let (x1, x2) = (a.0.min(b.0), a.0.max(b.0) + 1);
+        let (y1, y2) = (a.1.min(b.1), a.1.max(b.1) + 1);
+        let s = self.stride;
+        self.outside[y2 * s + x2] + self.outside[y1 * s + x1]
+            == self.outside[y1 * s + x2] + self.outside[y2 * s + x1]
+    }
+
+    /// The lattice points cell `i` covers along an axis, as a closed range.
+    /// An empty gap gives a range that ends just before it starts.
+    fn span(values: &[i64], i: usize) -> (i64, i64) {
+        if i.is_multiple_of(2) {
+            (values[i / 2], values[i / 2])
+        } else {
+            (values[i / 2] + 1, values[i / 2 + 1] - 1)
+        }
+    }
+
+    /// Opposite corners of the largest rectangle of lattice points inside the
+    /// polygon, wherever its corners are.
+    ///
+    /// Sweeps down the rows of cells tracking how many lattice points each
+    /// column reaches up from the current row, then finds the widest span for
+    /// each reach with a stack, as for the largest rectangle in a histogram.
+    pub fn largest_rectangle(&self) -> Option<(Point<2>, Point<2>)> {
+        let width = self.stride - 1;
+        let height = self.outside.len() / self.stride - 1;
+        let mut reach = vec![0; width];
+        let mut best: Option<(u64, Point<2>, Point<2>)> = None;
+
+        for y in 0..height {
+            let (top, bottom) = Self::span(&self.ys, y);
+            let rows = (bottom - top + 1) as u64;
+            for (x, r) in reach.iter_mut().enumerate() {
+                *r = if self.contains_cells((x, y), (x, y)) {
+                    *r + rows
+                } else {
+                    0
+                };
+            }
+            if rows == 0 {
+                continue;
+            }
+
+            // (first column, reach) with reach increasing up the stack
+            let mut stack: Vec<(usize, u64)> = Vec::new();
+            for x in 0..=width {
+                let r = reach.get(x).copied().unwrap_or(0);
+                let mut start = x;
+                while let Some(&(first, up)) = stack.last()
+                    && up >= r
+                {
+                    stack.pop();
+                    // Columns first..x all reach at least `up`
+                    let left = Self::span(&self.xs, first).0;
+                    let right = Self::span(&self.xs, x - 1).1;
+                    let area = (right - left + 1) as u64 * up;
+                    if area > 0 && best.is_none_or(|(b, ..)| area > b) {
+                        let top = bottom + 1 - up as i64;
+                        best = Some((area, Point([left, top]), Point([right, bottom])));
+                    }
+                    start = first;
+                }
+                stack.push((start, r));
+            }
+        }
+        best.map(|(_, a, b)| (a, b))
+    }
+
+    /// Whether every lattice point of the rectangle with corners `a` and `b`
+    /// is inside the polygon or on its outline.
+    pub fn contains_rectangle(&self, a: Point<2>, b: Point<2>) -> bool {
+        match (self.cell(a), self.cell(b)) {
+            (Some(a), Some(b)) => self.contains_cells(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// A random simple polygon that is x-monotone: each of `columns` vertical
+/// strips spans from a bottom to a top that overlaps its neighbours'. Every
+/// other polygon is mirrored along the diagonal to make it y-monotone instead.
+#[cfg(test)]
+pub(crate) fn random_polygon(state: &mut u64, columns: usize, scale: i64) -> RectilinearPolygon {
+    use crate::xorshift;
+    let mut random = |n: i64| (xorshift(state) % n as u64) as i64;
+
+    let mut xs = vec![random(scale)];
+    for _ in 0..columns {
+        xs.push(xs[xs.len() - 1] + 1 + random(scale));
+    }
+    let mut spans: Vec<(i64, i64)> = Vec::new();
+    while spans.len() < columns {
+        let bottom = random(4 * scale);
+        let top = bottom + 1 + random(4 * scale);
+        if spans.last().is_none_or(|&(b, t)| bottom < t && b < top) {
+            spans.push((bottom, top));
+        }
+    }
+
+    let mut outline: Vec<Point<2>> = Vec::new();
+    for (i, &(_, top)) in spans.iter().enumerate() {
+        outline.push(Point([xs[i], top]));
+        outline.push(Point([xs[i + 1], top]));
+    }
+    for (i, &(bottom, _)) in spans.iter().enumerate().rev() {
+        outline.push(Point([xs[i + 1], bottom]));
+        outline.push(Point([xs[i], bottom]));
+    }
+
+    // Drop repeated points, then points in the middle of a straight edge
+    outline.dedup();
+    let n = outline.len();
+    let mut corners: Vec<Point<2>> = (0..n)
+        .filter(|&i| {
+            let (p, q, r) = (outline[(i + n - 1) % n], outline[i], outline[(i + 1) % n]);
+            !(p.x() == q.x() && q.x() == r.x() || p.y() == q.y() && q.y() == r.y())
+        })
+        .map(|i| outline[i])
+        .collect();
+    if random(2) == 0 {
+        corners = corners.into_iter().map(|p| Point([p.y(), p.x()])).collect();
+    }
+    RectilinearPolygon::new(corners).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xorshift;
+
+    /// The example from day 9 of Advent of Code 2025.
+    fn example() -> RectilinearPolygon {
+        let corners = [
+            [7, 1],
+            [11, 1],
+            [11, 7],
+            [9, 7],
+            [9, 5],
+            [2, 5],
+            [2, 3],
+            [7, 3],
+        ];
+        RectilinearPolygon::new(corners.map(Point).to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_area_and_lattice_points() {
+        let polygon = example();
+        assert_eq!(polygon.area(), 30);
+        assert_eq!(polygon.boundary_points(), 30);
+        assert_eq!(polygon.interior_points(), 16);
+        assert_eq!(polygon.lattice_points(), 46);
+        assert_eq!(polygon.bounds(), (Point([2, 1]), Point([11, 7])));
+    }
+
+    #[test]
+    fn test_contains() {
+        let polygon = example();
+        for (p, inclusive, exclusive) in [
+            (Point([7, 1]), true, false),
+            (Point([8, 2]), true, true),
+            (Point([5, 4]), true, true),
+            (Point([2, 4]), true, false),
+            (Point([4, 2]), false, false),
+            (Point([8, 6]), false, false),
+            (Point([10, 6]), true, true),
+            (Point([12, 4]), false, false),
+        ] {
+            assert_eq!(
+                polygon.contains(p, Boundary::Inclusive),
+                inclusive,
+                "{:?}",
+                p
+            );
+            assert_eq!(
+                polygon.contains(p, Boundary::Exclusive),
+                exclusive,
+                "{:?}",
+                p
+            );
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let new = |v: &[[i64; 2]]| RectilinearPolygon::new(v.iter().copied().map(Point).collect());
+        assert_eq!(
+            new(&[[0, 0], [1, 0], [1, 1]]),
+            Err(PolygonError::TooFewVertices(3))
+        );
+        assert_eq!(
+            new(&[[0, 0], [2, 0], [2, 0], [2, 2], [0, 2]]),
+            Err(PolygonError::DuplicateVertex { index: 2 })
+        );
+        assert_eq!(
+            new(&[[0, 0], [2, 0], [2, 2], [1, 3], [0, 2]]),
+            Err(PolygonError::DiagonalEdge { index: 2 })
+        );
+        // The closing edge must be axis-aligned too
+        assert_eq!(
+            new(&[[0, 0], [2, 0], [2, 2], [1, 2]]),
+            Err(PolygonError::DiagonalEdge { index: 3 })
+        );
+        // A bow tie made of two squares touching at (2, 2)
+        assert_eq!(
+            new(&[[0, 0], [2, 0], [2, 4], [4, 4], [4, 2], [0, 2]]),
+            Err(PolygonError::SelfIntersection {
+                first: 1,
+                second: 4
+            })
+        );
+        // Running back along the previous edge
+        assert_eq!(
+            new(&[[0, 0], [4, 0], [2, 0], [2, 2], [0, 2]]),
+            Err(PolygonError::SelfIntersection {
+                first: 0,
+                second: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_random_polygons() {
+        let mut state = 0x2025_0042;
+        for _ in 0..300 {
+            let columns = 1 + (xorshift(&mut state) % 6) as usize;
+            let scale = 1 + (xorshift(&mut state) % 5) as i64;
+            let polygon = random_polygon(&mut state, columns, scale);
+            let index = polygon.tile_index();
+
+            let (Point([min_x, min_y]), Point([max_x, max_y])) = polygon.bounds();
+            let (mut inside, mut interior) = (0, 0);
+            for y in min_y - 1..=max_y + 1 {
+                for x in min_x - 1..=max_x + 1 {
+                    let p = Point([x, y]);
+                    let contained = polygon.contains(p, Boundary::Inclusive);
+                    assert_eq!(
+                        index.contains_rectangle(p, p),
+                        contained,
+                        "{:?} {:?}",
+                        p,
+                        polygon
+                    );
+                    inside += i128::from(contained);
+                    interior += i128::from(polygon.contains(p, Boundary::Exclusive));
+                }
+            }
+            assert_eq!(polygon.lattice_points(), inside, "{:?}", polygon);
+            assert_eq!(polygon.interior_points(), interior, "{:?}", polygon);
+
+            // A rectangle is inside exactly when all its lattice points are
+            let v = polygon.vertices();
+            let (a, b) = (v[0], v[v.len() / 2]);
+            let all_inside = (a.y().min(b.y())..=a.y().max(b.y())).all(|y| {
+                (a.x().min(b.x())..=a.x().max(b.x()))
+                    .all(|x| polygon.contains(Point([x, y]), Boundary::Inclusive))
+            });
+            assert_eq!(index.contains_rectangle(a, b), all_inside, "{:?}", polygon);
+        }
+    }
+
+    #[test]
+    fn test_largest_rectangle() {
+        let (a, b) = example().tile_index().largest_rectangle().unwrap();
+        assert_eq!((a, b), (Point([2, 3]), Point([11, 5])));
+
+        // Compare areas with every rectangle in the bounding box
+        let mut state = 0x2025_0044;
+        for _ in 0..100 {
+            let columns = 1 + (xorshift(&mut state) % 5) as usize;
+            let scale = 1 + (xorshift(&mut state) % 3) as i64;
+            let polygon = random_polygon(&mut state, columns, scale);
+            let (Point([min_x, min_y]), Point([max_x, max_y])) = polygon.bounds();
+
+            let mut best = 0;
+            for y1 in min_y..=max_y {
+                for x1 in min_x..=max_x {
+                    // Grow downwards, narrowing to the widest run inside on every row
+                    let mut right = max_x;
+                    for y2 in y1..=max_y {
+                        let run = (x1..=right)
+                            .take_while(|&x| polygon.contains(Point([x, y2]), Boundary::Inclusive))
+                            .last();
+                        let Some(r) = run else { break };
+                        right = r;
+                        best = best.max((right - x1 + 1) * (y2 - y1 + 1));
+                    }
+                }
+            }
+
+            let (a, b) = polygon.tile_index().largest_rectangle().unwrap();
+            assert_eq!(
+                (b.x() - a.x() + 1) * (b.y() - a.y() + 1),
+                best,
+                "{:?}",
+                polygon
+            );
+            assert!(polygon.tile_index().contains_rectangle(a, b));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::answer::Answer;
+
+/// The digits picked from a string, in order, with the positions they came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subsequence {
+    pub indices: Vec<usize>,
+    pub digits: String,
+}
+
+impl Subsequence {
+    /// The picked digits as a number, or `None` if it does not fit in a `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        self.digits.parse().ok()
+    }
+
+    /// The picked digits as a number of any size.
+    pub fn value(&self) -> Answer {
+        if self.digits.is_empty() {
+            return Answer::default();
+        }
+        self.digits.parse().expect("subsequence only holds digits")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubsequenceError {
+    /// Asked for `k` digits from a string of only `len`
+    TooShort { len: usize, k: usize },
+    /// The character at `index` is not an ASCII digit
+    NotADigit { index: usize, c: char },
+}
+
+impl std::fmt::Display for SubsequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SubsequenceError::TooShort { len, k } => {
+                write!(f, "cannot pick {} digits from {}", k, len)
+            }
+            SubsequenceError::NotADigit { index, c } => {
+                write!(f, "{:?} at position {} is not a digit", c, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubsequenceError {}
+
+/// The largest number formed by keeping `k` of the digits in `s`, in order.
+///
+/// # Example
+/// ```
+/// # use advent_of_code_2025::subsequence::largest_subsequence;
+/// let best = largest_subsequence("818181911112111", 4).unwrap();
+/// assert_eq!(best.digits, "9211");
+/// assert_eq!(best.indices, vec![6, 11, 12, 13]);
+/// ```
+pub fn largest_subsequence(s: &str, k: usize) -> Result<Subsequence, SubsequenceError> {
+    select(s, k, |top, d| top < d)
+}
+
+/// The smallest number formed by keeping `k` of the digits in `s`, in order.
+pub fn smallest_subsequence(s: &str, k: usize) -> Result<Subsequence, SubsequenceError> {
+    select(s, k, |top, d| top > d)
+}
+
+/// Monotonic stack: a digit evicts the kept digits it beats as long as enough
+/// digits remain to still pick `k`. Each digit is pushed and popped at most
+/// once, so this is O(n).
+fn select<F>(s: &str, k: usize, beats: F) -> Result<Subsequence, SubsequenceError>
+where
+    F: Fn(u8, u8) -> bool,
+{
+    let digits = s
+        .chars()
+        .enumerate()
+        .map(|(index, c)| match c {
+            '0'..='9' => Ok(c as u8),
+            _ => Err(SubsequenceError::NotADigit { index, c }),
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    let len = digits.len();
+    if len < k {
+        return Err(SubsequenceError::TooShort { len, k });
+    }
+
+    let mut stack: Vec<usize> = Vec::with_capacity(k);
+    for (i, &d) in digits.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            if stack.len() + (len - i) > k && beats(digits[top], d) {
+                stack.pop();
+            } else {
+                break;
+            }
+        }
+        if stack.len() < k {
+            stack.push(i);
+        }
+    }
+
+    Ok(Subsequence {
+        digits: stack.iter().map(|&i| digits[i] as char).collect(),
+        indices: stack,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xorshift;
+
+    /// Try every choice of `k` positions.
+    fn brute_force(s: &str, k: usize) -> (String, String) {
+        let n = s.len();
+        let picks = (0u32..1 << n)
+            .filter(|m| m.count_ones() as usize == k)
+            .map(|m| {
+                (0..n)
+                    .filter(|i| m & (1 << i) != 0)
+                    .map(|i| &s[i..=i])
+                    .collect::<String>()
+            });
+        (picks.clone().max().unwrap(), picks.min().unwrap())
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut state = 0x2025_0003;
+        for _ in 0..300 {
+            let n = 1 + (xorshift(&mut state) % 12) as usize;
+            let s: String = (0..n)
+                .map(|_| char::from(b'0' + (xorshift(&mut state) % 4) as u8))
+                .collect();
+            let k = (xorshift(&mut state) % (n as u64 + 1)) as usize;
+
+            let (max, min) = brute_force(&s, k);
+            let largest = largest_subsequence(&s, k).unwrap();
+            let smallest = smallest_subsequence(&s, k).unwrap();
+            assert_eq!(largest.digits, max, "{} k={}", s, k);
+            assert_eq!(smallest.digits, min, "{} k={}", s, k);
+
+            for sub in [largest, smallest] {
+                assert!(sub.indices.windows(2).all(|w| w[0] < w[1]));
+                let picked: String = sub.indices.iter().map(|&i| &s[i..=i]).collect();
+                assert_eq!(picked, sub.digits);
+            }
+        }
+    }
+
+    #[test]
+    fn test_more_digits_than_u64() {
+        let bank = "9".repeat(30);
+        let best = largest_subsequence(&bank, 25).unwrap();
+        assert_eq!(best.digits.len(), 25);
+        assert_eq!(best.to_u64(), None);
+        assert_eq!(best.value().to_string(), "9".repeat(25));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            largest_subsequence("12345", 6),
+            Err(SubsequenceError::TooShort { len: 5, k: 6 })
+        );
+        assert_eq!(
+            smallest_subsequence("12a45", 2),
+            Err(SubsequenceError::NotADigit { index: 2, c: 'a' })
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::export::{Palette, Renderer};
+use crate::{Grid, Options};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+/// One snapshot of a grid, with a caption such as `Round 3: removed 12 rolls`.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    grid: Grid<char>,
+    caption: String,
+}
+
+impl Frame {
+    pub(crate) fn new(grid: Grid<char>, caption: impl Into<String>) -> Self {
+        Frame {
+            grid,
+            caption: caption.into(),
+        }
+    }
+
+    pub fn caption(&self) -> &str {
+        &self.caption
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// The cells, one row at a time.
+    pub fn rows(&self) -> impl Iterator<Item = &[char]> {
+        self.grid.data.chunks(self.grid.width)
+    }
+
+    /// The caption, then one line per grid row.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", self.caption);
+        for row in self.rows() {
+            text.extend(row);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Like `to_text`, with cells coloured using ANSI escape codes.
+    pub fn to_ansi(&self) -> String {
+        let mut text = format!("\x1b[1m{}\x1b[0m\n", self.caption);
+        for row in self.rows() {
+            let mut current = None;
+            for &c in row {
+                let colour = ansi_colour(c);
+                if colour != current {
+                    match colour {
+                        Some(code) => text.push_str(&format!("\x1b[{}m", code)),
+                        None => text.push_str("\x1b[0m"),
+                    }
+                    current = colour;
+                }
+                text.push(c);
+            }
+            if current.is_some() {
+                text.push_str("\x1b[0m");
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Foreground colour for a cell, or `None` to leave it plain.
+fn ansi_colour(c: char) -> Option<u8> {
+    match c {
+        '@' => Some(33), // rolls of paper
+        'x' => Some(31), // rolls removed this round
+        'S' => Some(32), // beam start
+        '|' => Some(36), // beams
+        '^' => Some(35), // splitters
+        '#' => Some(91), // red tiles
+        'X' => Some(92), // green tiles
+        'O' => Some(93), // the chosen rectangle
+        _ => None,
+    }
+}
+
+/// Write each frame as plain text to `frame_0000.txt`, `frame_0001.txt`, ... in `dir`.
+pub fn write_text(frames: &[Frame], dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for (i, frame) in frames.iter().enumerate() {
+        std::fs::write(dir.join(format!("frame_{:04}.txt", i)), frame.to_text())?;
+    }
+    Ok(())
+}
+
+/// The single entry point for days: build frames with `frames` only if one
+/// of these options was given, then output them as asked:
+///
+/// - `--visualize` plays them in the terminal, `--fps` sets the speed
+///   (default 10 frames per second)
+/// - `--frames-dir <dir>` saves them as plain text
+/// - `--png <dir>` and `--gif <file>` save them as images, with each cell
+///   drawn as a square of `--cell-size` pixels (default 4)
+pub fn run(options: &Options, frames: impl FnOnce() -> Vec<Frame>) {
+    if requested(options) {
+        show(&frames(), options);
+    }
+}
+
+fn requested(options: &Options) -> bool {
+    options.flag("--visualize")
+        || ["--frames-dir", "--png", "--gif"]
+            .iter()
+            .any(|name| options.flag(name))
+}
+
+fn show(frames: &[Frame], options: &Options) {
+    if let Some(dir) = options.value::<String>("--frames-dir") {
+        write_text(frames, Path::new(&dir))
+            .unwrap_or_else(|e| panic!("Failed to write frames to {}: {}", dir, e));
+    }
+
+    let fps: f64 = options.value("--fps").unwrap_or(10.0);
+    assert!(fps > 0.0, "--fps must be positive");
+    let delay = Duration::from_secs_f64(1.0 / fps);
+
+    let renderer = Renderer::new(
+        Palette::default(),
+        options.value("--cell-size").unwrap_or(4),
+    );
+    if let Some(dir) = options.value::<String>("--png") {
+        renderer
+            .write_pngs(frames, Path::new(&dir))
+            .unwrap_or_else(|e| panic!("Failed to write images to {}: {}", dir, e));
+    }
+    if let Some(path) = options.value::<String>("--gif") {
+        renderer
+            .write_gif(frames, delay, Path::new(&path))
+            .unwrap_or_else(|e| panic!("Failed to write {}: {}", path, e));
+    }
+
+    if !options.flag("--visualize") {
+        return;
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    for frame in frames {
+        // Clear the screen and move the cursor home, so frames replace each other
+        write!(stdout, "\x1b[2J\x1b[H{}", frame.to_ansi()).expect("Failed to write frame");
+        stdout.flush().expect("Failed to write frame");
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_and_ansi() {
+        let frame = Frame::new(Grid::parse("@@.\n.x|"), "Round 1");
+        assert_eq!(frame.to_text(), "Round 1\n@@.\n.x|\n");
+        assert_eq!(
+            frame.to_ansi(),
+            "\x1b[1mRound 1\x1b[0m\n\x1b[33m@@\x1b[0m.\n.\x1b[31mx\x1b[36m|\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_write_text() {
+        let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let frames = [
+            Frame::new(Grid::parse("@"), "a"),
+            Frame::new(Grid::parse("."), "b"),
+        ];
+        write_text(&frames, &dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("frame_0001.txt")).unwrap(),
+            "b\n.\n"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}